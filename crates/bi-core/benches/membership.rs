@@ -0,0 +1,62 @@
+//! Benchmark: rule trigger checks against a bitset vs a hash set.
+//!
+//! 2k rules of 64 triggers each, all active except the last one, so every
+//! check walks the whole trigger list. This is the inner loop of `tick`.
+//!
+//! Run with `cargo bench -p bi-core --bench membership`.
+
+use bi_core::{FlagId, FlagSet, Mind};
+use std::collections::HashSet;
+use std::hint::black_box;
+use std::time::Instant;
+
+const RULES: usize = 2_000;
+const TRIGGERS: usize = 64;
+const ROUNDS: u32 = 50;
+
+fn main() {
+    let mut mind = Mind::new();
+    for i in 0..RULES {
+        let mut triggers: Vec<String> = (0..TRIGGERS - 1).map(|t| format!("Cue{}", t)).collect();
+        triggers.push(format!("Missing{}", i));
+        let triggers: Vec<&str> = triggers.iter().map(String::as_str).collect();
+        mind.learn(&triggers, &format!("Out{}", i));
+    }
+    let active: Vec<FlagId> = (0..TRIGGERS - 1)
+        .map(|t| mind.id(&format!("Cue{}", t)))
+        .collect();
+    let bits: FlagSet = active.iter().copied().collect();
+    let hashed: HashSet<FlagId> = active.iter().copied().collect();
+
+    // Baseline: the same AND gate over a `HashSet`.
+    let hashing = Instant::now();
+    for _ in 0..ROUNDS {
+        let fired = mind
+            .rules()
+            .iter()
+            .filter(|rule| rule.triggers.iter().all(|t| hashed.contains(t)))
+            .count();
+        assert_eq!(black_box(fired), 0);
+    }
+    let hashing = hashing.elapsed() / ROUNDS;
+
+    let bitset = Instant::now();
+    for _ in 0..ROUNDS {
+        let fired = mind
+            .rules()
+            .iter()
+            .filter(|rule| mind.fires(rule, &bits))
+            .count();
+        assert_eq!(black_box(fired), 0);
+    }
+    let bitset = bitset.elapsed() / ROUNDS;
+
+    println!(
+        "{} rules x {} triggers: hash set {:?}, bitset {:?} ({:.1}x)",
+        RULES,
+        TRIGGERS,
+        hashing,
+        bitset,
+        hashing.as_secs_f64() / bitset.as_secs_f64()
+    );
+}
@@ -0,0 +1,54 @@
+//! Benchmark: `ponder` vs `ponder_compiled` on a wide tick.
+//!
+//! One input wakes 200k rules at once, plain and grouped; each needs a
+//! second flag that never comes, so the tick is all evaluation and no
+//! commits. The plan is compiled before timing starts.
+//!
+//! Run with `cargo bench -p bi-core --bench tick_compiled`.
+
+use bi_core::Mind;
+use std::time::Instant;
+
+const WIDE_RULES: usize = 200_000;
+const ROUNDS: u32 = 10;
+
+fn main() {
+    let mut mind = Mind::new();
+    for i in 0..WIDE_RULES {
+        let never = format!("Never{}", i);
+        let quiet = format!("Quiet{}", i);
+        if i % 2 == 0 {
+            mind.learn(&["Go", &never], &quiet);
+        } else {
+            mind.rule_or(&["Go"], &[&never, "Nobody"], &["Stop"], &quiet);
+        }
+    }
+    mind.learn(&["Go"], "Went");
+    mind.compile();
+
+    let naive = Instant::now();
+    for _ in 0..ROUNDS {
+        mind.reset_memory();
+        mind.inject(&["Go"]);
+        mind.ponder();
+    }
+    let naive = naive.elapsed() / ROUNDS;
+    let expected = mind.active_labels();
+
+    let compiled = Instant::now();
+    for _ in 0..ROUNDS {
+        mind.reset_memory();
+        mind.inject(&["Go"]);
+        mind.ponder_compiled();
+    }
+    let compiled = compiled.elapsed() / ROUNDS;
+    assert_eq!(mind.active_labels(), expected);
+
+    println!(
+        "\n{} rules in one tick: per-rule vectors {:?}, compiled plan {:?} ({:.1}x)",
+        WIDE_RULES + 1,
+        naive,
+        compiled,
+        naive.as_secs_f64() / compiled.as_secs_f64()
+    );
+}
@@ -0,0 +1,53 @@
+//! Benchmark: indexed `ponder` vs a full rule scan per tick.
+//!
+//! The knowledge base holds 10k rules that never fire plus a short chain
+//! that does. A full scan re-checks all 10k rules on every tick of the chain;
+//! the trigger index only re-checks the rules whose flags changed.
+//!
+//! Run with `cargo bench -p bi-core`.
+
+use bi_core::{FlagSet, Mind};
+use std::time::Instant;
+
+const NOISE_RULES: usize = 10_000;
+const CHAIN: usize = 32;
+const ROUNDS: u32 = 10;
+
+fn main() {
+    let mut mind = Mind::new();
+    for i in 0..NOISE_RULES {
+        let a = format!("Noise{}", i);
+        let b = format!("Never{}", i);
+        mind.learn(&[&a, &b], &format!("Quiet{}", i));
+    }
+    for i in 0..CHAIN {
+        mind.learn(&[&format!("Step{}", i)], &format!("Step{}", i + 1));
+    }
+
+    // Baseline: `settle` scans every rule on every step.
+    let start_id = mind.id("Step0");
+    let full_scan = Instant::now();
+    for _ in 0..ROUNDS {
+        let settled = mind.settle(FlagSet::from([start_id]));
+        assert_eq!(settled.len(), CHAIN + 1);
+    }
+    let full_scan = full_scan.elapsed() / ROUNDS;
+
+    // Indexed: `ponder` (also printing every derivation).
+    let indexed = Instant::now();
+    for _ in 0..ROUNDS {
+        mind.reset_memory();
+        mind.inject(&["Step0"]);
+        mind.ponder();
+    }
+    let indexed = indexed.elapsed() / ROUNDS;
+
+    println!(
+        "\n{} rules, {} ticks: full scan {:?}, indexed {:?} ({:.1}x)",
+        NOISE_RULES + CHAIN,
+        CHAIN,
+        full_scan,
+        indexed,
+        full_scan.as_secs_f64() / indexed.as_secs_f64()
+    );
+}
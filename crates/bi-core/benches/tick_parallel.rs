@@ -0,0 +1,46 @@
+//! Benchmark: `ponder` vs `ponder_parallel` on a wide tick.
+//!
+//! One input wakes 200k rules at once; each needs a second flag that never
+//! comes, so the tick is all evaluation and no commits.
+//!
+//! Run with `cargo bench -p bi-core --features parallel`.
+
+use bi_core::Mind;
+use std::time::Instant;
+
+const WIDE_RULES: usize = 200_000;
+const ROUNDS: u32 = 10;
+
+fn main() {
+    let mut mind = Mind::new();
+    for i in 0..WIDE_RULES {
+        mind.learn(&["Go", &format!("Never{}", i)], &format!("Quiet{}", i));
+    }
+    mind.learn(&["Go"], "Went");
+
+    let sequential = Instant::now();
+    for _ in 0..ROUNDS {
+        mind.reset_memory();
+        mind.inject(&["Go"]);
+        mind.ponder();
+    }
+    let sequential = sequential.elapsed() / ROUNDS;
+    let expected = mind.active_labels();
+
+    let parallel = Instant::now();
+    for _ in 0..ROUNDS {
+        mind.reset_memory();
+        mind.inject(&["Go"]);
+        mind.ponder_parallel();
+    }
+    let parallel = parallel.elapsed() / ROUNDS;
+    assert_eq!(mind.active_labels(), expected);
+
+    println!(
+        "\n{} rules in one tick: sequential {:?}, parallel {:?} ({:.1}x)",
+        WIDE_RULES + 1,
+        sequential,
+        parallel,
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
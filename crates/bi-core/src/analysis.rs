@@ -0,0 +1,1350 @@
+//! Offline analysis of the topology and the current memory.
+//! Nothing here prints or changes the mind it inspects.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+
+use crate::collections::{BTreeSet, HashMap, HashSet};
+use crate::engine::{Fired, Mind, Source};
+use crate::error::MindError;
+use crate::flagset::FlagSet;
+use crate::report::NullReporter;
+use crate::rules::Rule;
+use crate::symbols::FlagId;
+
+/// One scenario of `enumerate_outcomes`: (inputs added, conclusions), both sorted.
+pub type Outcome = (Vec<String>, Vec<String>);
+
+/// Why two flags can never be active together:
+/// every rule producing `suppressed` is forbidden by something `by` implies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExclusivityReason {
+    pub suppressed: String,
+    pub by: String,
+    pub chains: Vec<(usize, Vec<String>)>, // (rule index, `by` -> ... -> forbidden flag)
+}
+
+impl fmt::Display for ExclusivityReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "`{}` is kept off by `{}`:", self.suppressed, self.by)?;
+        for (index, chain) in &self.chains {
+            let chain = chain
+                .iter()
+                .map(|l| format!("`{}`", l))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            writeln!(f, "  {} forbids link #{}", chain, index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why `tick` halted (see `set_halt_on_contradiction`): the exclusive pair
+/// that tick `tick` would have made hold together, and the rules deriving
+/// either side, by index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contradiction {
+    pub tick: usize,
+    pub flags: (String, String),
+    pub rules: Vec<usize>,
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tick {}: `{}` and `{}` would hold together",
+            self.tick, self.flags.0, self.flags.1
+        )?;
+        if !self.rules.is_empty() {
+            let rules: Vec<String> = self.rules.iter().map(|r| format!("#{}", r)).collect();
+            write!(f, " (rules {})", rules.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Mind<T> {
+    // ========================================================================
+    // Transformation
+    // ========================================================================
+
+    /// Partial evaluation: a copy of this mind with `always_true` baked in.
+    /// Background triggers are dropped from rules, and rules that a background
+    /// flag forbids (or whose output is background) are pruned, since they
+    /// can never fire. Run the result on the remaining inputs only.
+    /// Settings carry over: the activation thresholds, the trigger mode,
+    /// exclusive pairs and declared inputs and outputs. Rules keep their
+    /// version stamps, but the changelog starts over at the current version:
+    /// its rule indices would not fit the pruned rules.
+    /// Votes are not carried over: background weights cannot be folded into them.
+    /// Custom rules are copied as they are: their gates are opaque, so a
+    /// background flag they read is not folded in and counts as absent
+    /// unless it is injected into the result too.
+    pub fn specialize(&self, always_true: &[&str]) -> Mind {
+        let background: HashSet<FlagId> = always_true
+            .iter()
+            .filter_map(|n| self.symbols.get(n))
+            .collect();
+
+        let mut specialized = Mind::new();
+        specialized.symbols = self.symbols.clone();
+        specialized.reporter = self.reporter.clone();
+        specialized.group_names = self.group_names.clone();
+        specialized.disabled_groups = self.disabled_groups.clone();
+        specialized.group_gates = self.group_gates.clone();
+        specialized.custom_rules = self.custom_rules.clone();
+        specialized.rule_version = self.rule_version;
+        specialized.exclusive_pairs = self.exclusive_pairs.clone();
+        specialized.declared_inputs = self.declared_inputs.clone();
+        specialized.declared_outputs = self.declared_outputs.clone();
+        specialized.activation_threshold = self.activation_threshold;
+        specialized.output_thresholds = self.output_thresholds.clone();
+        specialized.trigger_mode = self.trigger_mode;
+        specialized.rules = self
+            .rules
+            .iter()
+            .filter(|rule| !background.contains(&rule.output))
+            .filter(|rule| !rule.forbids.iter().any(|f| background.contains(f)))
+            .filter_map(|rule| {
+                let members = rule
+                    .any_of
+                    .iter()
+                    .filter(|a| background.contains(*a))
+                    .count();
+                let mut forbids = rule.forbids.clone();
+                let rest = || {
+                    rule.any_of
+                        .iter()
+                        .copied()
+                        .filter(|a| !background.contains(a))
+                        .collect::<Vec<_>>()
+                };
+                let (any_of, quorum) = match (members, rule.exclusive) {
+                    (0, _) => (rule.any_of.clone(), rule.quorum),
+                    // Background members count towards the quorum for good.
+                    (m, false) if m >= rule.quorum => (Vec::new(), 1),
+                    (m, false) => (rest(), rule.quorum - m),
+                    // One background member rules out every other member of an exclusive group.
+                    (1, true) => {
+                        forbids.extend(rest());
+                        (Vec::new(), 1)
+                    }
+                    _ => return None,
+                };
+                Some(Rule {
+                    triggers: rule
+                        .triggers
+                        .iter()
+                        .copied()
+                        .filter(|t| !background.contains(t))
+                        .collect(),
+                    exclusive: rule.exclusive && !any_of.is_empty(),
+                    any_of,
+                    quorum,
+                    forbids,
+                    output: rule.output,
+                    strict: rule.strict,
+                    priority: rule.priority,
+                    min_confidence: rule.min_confidence,
+                    group: rule.group,
+                    weight: rule.weight,
+                    added_at: rule.added_at,
+                    modified_at: rule.modified_at,
+                })
+            })
+            .collect();
+        specialized.reindex();
+        specialized
+    }
+
+    // ========================================================================
+    // Lints
+    // ========================================================================
+
+    /// Declared inputs that some rule or vote can also produce.
+    /// Deployments separating observations (sensors) from conclusions
+    /// treat every entry as a violation.
+    pub fn check_io_separation(&self, declared_inputs: &[&str]) -> Vec<String> {
+        let produced: HashSet<FlagId> = self
+            .rules
+            .iter()
+            .map(|rule| rule.output)
+            .chain(
+                self.votes
+                    .iter()
+                    .flat_map(|v| v.candidates.iter().map(|(o, _)| *o)),
+            )
+            .collect();
+
+        let mut violations: Vec<String> = declared_inputs
+            .iter()
+            .filter(|n| self.symbols.get(n).is_some_and(|id| produced.contains(&id)))
+            .map(|n| n.to_string())
+            .collect();
+        violations.sort();
+        violations.dedup();
+        violations
+    }
+
+    /// One loop in the rule graph (trigger or group member -> output), if any,
+    /// as the flags along it in order; the last one leads back to the first.
+    /// Forbids are not followed. Checks the static rules, not active memory.
+    pub fn has_cycles(&self) -> Option<Vec<FlagId>> {
+        let mut edges: HashMap<FlagId, Vec<FlagId>> = HashMap::new();
+        for rule in &self.rules {
+            for cause in rule.triggers.iter().chain(&rule.any_of) {
+                edges.entry(*cause).or_default().push(rule.output);
+            }
+        }
+
+        // Depth-first search; `path` is the grey stack, `done` the black set.
+        let mut done: HashSet<FlagId> = HashSet::new();
+        for start in self.symbols.ids() {
+            if done.contains(&start) {
+                continue;
+            }
+            let mut path: Vec<FlagId> = vec![start];
+            let mut next: Vec<usize> = vec![0];
+            while let Some(&node) = path.last() {
+                let depth = path.len() - 1;
+                let successors = edges.get(&node).map_or(&[][..], Vec::as_slice);
+                match successors.get(next[depth]) {
+                    Some(&succ) => {
+                        next[depth] += 1;
+                        if let Some(pos) = path.iter().position(|&p| p == succ) {
+                            return Some(path[pos..].to_vec());
+                        }
+                        if !done.contains(&succ) {
+                            path.push(succ);
+                            next.push(0);
+                        }
+                    }
+                    None => {
+                        done.insert(node);
+                        path.pop();
+                        next.pop();
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Declare the flags sensors may inject, so that `dead_rules` can tell
+    /// a misspelled trigger from a genuine input.
+    pub fn declare_inputs(&mut self, labels: &[&str]) {
+        for label in labels {
+            let id = self.id(label);
+            self.declared_inputs.insert(id);
+        }
+    }
+
+    /// Declare the flags the embedder queries, so that `unused_outputs`
+    /// does not report the conclusions the rule base exists for.
+    pub fn declare_outputs(&mut self, labels: &[&str]) {
+        for label in labels {
+            let id = self.id(label);
+            self.declared_outputs.insert(id);
+        }
+    }
+
+    /// Flags rules or votes read (as trigger, group member or forbid) that
+    /// no rule or vote produces and that are not declared inputs, sorted:
+    /// likely typos or a sensor nobody wired up. Without `declare_inputs`
+    /// this lists every flag the world has to supply.
+    pub fn orphan_triggers(&self) -> Vec<String> {
+        let produced = self.produced_ids();
+        let mut labels: Vec<String> = self
+            .read_ids()
+            .into_iter()
+            .filter(|id| !produced.contains(id) && !self.declared_inputs.contains(id))
+            .map(|id| self.label(id))
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Outputs of rules or votes that no rule or vote reads and that are not
+    /// declared outputs (see `declare_outputs`), sorted: conclusions nothing
+    /// builds on and nobody asked for.
+    pub fn unused_outputs(&self) -> Vec<String> {
+        let read = self.read_ids();
+        let mut labels: Vec<String> = self
+            .produced_ids()
+            .into_iter()
+            .filter(|id| !read.contains(id) && !self.declared_outputs.contains(id))
+            .map(|id| self.label(id))
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Every flag a rule or vote outputs.
+    pub(crate) fn produced_ids(&self) -> HashSet<FlagId> {
+        let votes = self
+            .votes
+            .iter()
+            .flat_map(|v| v.candidates.iter().map(|(o, _)| *o));
+        self.rules
+            .iter()
+            .map(|rule| rule.output)
+            .chain(votes)
+            .collect()
+    }
+
+    /// Every flag a rule or vote reads, group gates included.
+    fn read_ids(&self) -> HashSet<FlagId> {
+        let gates = self.group_gates.values();
+        let rules = self.rules.iter().flat_map(|rule| {
+            rule.triggers
+                .iter()
+                .chain(&rule.any_of)
+                .chain(&rule.forbids)
+        });
+        let votes = self.votes.iter().flat_map(|v| {
+            v.candidates
+                .iter()
+                .flat_map(|(_, t)| t.iter().map(|(id, _)| id))
+        });
+        rules.chain(votes).chain(gates).copied().collect()
+    }
+
+    /// Rules whose triggers can never all hold, whatever gets injected:
+    /// a static reachability pass over the rule graph, not over memory.
+    ///
+    /// Reachable are the declared inputs (see `declare_inputs`), or every flag
+    /// no rule or vote produces if none are declared, then every output of a
+    /// rule whose triggers and group quorum are reachable, and every vote
+    /// candidate with a reachable trigger. Forbids are assumed absent, so
+    /// this only finds rules missing something; `always_dead_rules` also
+    /// catches rules their own forbids rule out.
+    pub fn dead_rules(&self) -> Vec<usize> {
+        let mut reachable: HashSet<FlagId> = if self.declared_inputs.is_empty() {
+            let produced = self.produced_ids();
+            self.symbols
+                .ids()
+                .into_iter()
+                .filter(|id| !produced.contains(id))
+                .collect()
+        } else {
+            self.declared_inputs.iter().copied().collect()
+        };
+
+        let mut alive = vec![false; self.rules.len()];
+        loop {
+            let mut grew = false;
+            for (index, rule) in self.rules.iter().enumerate() {
+                if alive[index] || !rule.triggers.iter().all(|t| reachable.contains(t)) {
+                    continue;
+                }
+                let members = rule
+                    .any_of
+                    .iter()
+                    .filter(|a| reachable.contains(*a))
+                    .count();
+                if rule.any_of.is_empty() || members >= rule.quorum {
+                    alive[index] = true;
+                    grew |= reachable.insert(rule.output);
+                }
+            }
+            for vote in &self.votes {
+                for (out, triggers) in &vote.candidates {
+                    if triggers.iter().any(|(t, _)| reachable.contains(t)) {
+                        grew |= reachable.insert(*out);
+                    }
+                }
+            }
+            if !grew {
+                break;
+            }
+        }
+
+        (0..self.rules.len()).filter(|&i| !alive[i]).collect()
+    }
+
+    /// Rules another rule makes pointless, as (subsumed, subsumer) pairs sorted
+    /// by index: whenever the subsumed rule fires, the subsumer fires too, no
+    /// later in the tick and at least as strongly, so deleting the subsumed
+    /// one changes no conclusion. Of two identical rules the later is reported.
+    ///
+    /// E.g. `A -> C` subsumes `A + B -> C`, but not `A + B - E -> C` unless
+    /// it forbids `E` as well. See `subsumes` for groups and strict forbids.
+    pub fn redundant_rules(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (i, general) in self.rules.iter().enumerate() {
+            for (j, specific) in self.rules.iter().enumerate() {
+                if i == j || !Self::subsumes(general, specific) {
+                    continue;
+                }
+                if i < j || !Self::subsumes(specific, general) {
+                    pairs.push((j, i));
+                }
+            }
+        }
+        pairs.sort();
+        pairs
+    }
+
+    /// Whether `general` fires whenever `specific` does (same output).
+    ///
+    /// Its triggers and forbids must be subsets of the specific ones, and its
+    /// level no lower (a later level could miss a suppression this tick), and
+    /// its confidence bar no higher (fewer triggers never lower the fuzzy AND).
+    /// Its group must be met by the specific triggers alone, or be the
+    /// specific group with a lower quorum; an exclusive group only matches
+    /// the same exclusive group. Strict forbids need a strict specific rule.
+    /// Both must be in the same rule group, or both in none: a group
+    /// switched off on its own would take the subsumer with it.
+    fn subsumes(general: &Rule, specific: &Rule) -> bool {
+        let within = |small: &[FlagId], large: &[FlagId]| small.iter().all(|f| large.contains(f));
+        if general.output != specific.output
+            || general.group != specific.group
+            || general.priority < specific.priority
+            || general.min_confidence > specific.min_confidence
+            || (general.strict && !specific.strict)
+            || !within(&general.triggers, &specific.triggers)
+            || !within(&general.forbids, &specific.forbids)
+        {
+            return false;
+        }
+        if general.any_of.is_empty() {
+            return true;
+        }
+
+        let same_group =
+            within(&general.any_of, &specific.any_of) && within(&specific.any_of, &general.any_of);
+        if general.exclusive {
+            return specific.exclusive && same_group && general.quorum <= specific.quorum;
+        }
+        let given = general
+            .any_of
+            .iter()
+            .filter(|a| specific.triggers.contains(a))
+            .count();
+        given >= general.quorum
+            || (!specific.any_of.is_empty()
+                && within(&specific.any_of, &general.any_of)
+                && general.quorum <= specific.quorum)
+    }
+
+    /// Rules that never fire under any combination of `candidate_inputs`,
+    /// whether their triggers can never all hold or their forbids can never
+    /// all be avoided at the same time (e.g. a rule forbidding its own trigger,
+    /// or a flag some lower stratum always derives first). Each combination
+    /// settles through the same steps as `what_if`, so strata, priorities,
+    /// groups, votes and custom rules all take part.
+    ///
+    /// Runs 2^n fixpoints, so `n` is capped at `MAX_CANDIDATE_INPUTS`;
+    /// drop inputs that cannot reach the rules in question first.
+    pub fn always_dead_rules(&self, candidate_inputs: &[&str]) -> Result<Vec<usize>, MindError> {
+        const MAX_CANDIDATE_INPUTS: usize = 16;
+        if candidate_inputs.len() > MAX_CANDIDATE_INPUTS {
+            return Err(MindError::TooManyInputs {
+                analysis: "always_dead_rules",
+                given: candidate_inputs.len(),
+                cap: MAX_CANDIDATE_INPUTS,
+            });
+        }
+
+        let ids: Vec<FlagId> = candidate_inputs
+            .iter()
+            .filter_map(|n| self.symbols.get(n))
+            .collect();
+
+        let strata = self.current_strata();
+        let mut ever_fired = vec![false; self.rules.len()];
+        for mask in 0..(1usize << ids.len()) {
+            let mut active: FlagSet = ids
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, id)| *id)
+                .collect();
+
+            loop {
+                let (firing, outputs) = self.step_firing(&active, None, &strata);
+                for index in firing {
+                    ever_fired[index] = true;
+                }
+                if outputs.is_empty() {
+                    break;
+                }
+                active.extend(outputs);
+            }
+        }
+
+        Ok((0..self.rules.len()).filter(|&i| !ever_fired[i]).collect())
+    }
+
+    // ========================================================================
+    // Scenario Analysis
+    // ========================================================================
+
+    /// Explores every subset of `optional_inputs` on top of the current memory,
+    /// pairing each input subset with the conclusions it settles into.
+    /// Subsets reaching an already-seen outcome are skipped.
+    ///
+    /// Cost is 2^n fixpoint runs, so `n` is capped at `MAX_OPTIONAL_INPUTS`.
+    pub fn enumerate_outcomes(&self, optional_inputs: &[&str]) -> Result<Vec<Outcome>, MindError> {
+        const MAX_OPTIONAL_INPUTS: usize = 16;
+        if optional_inputs.len() > MAX_OPTIONAL_INPUTS {
+            return Err(MindError::TooManyInputs {
+                analysis: "enumerate_outcomes",
+                given: optional_inputs.len(),
+                cap: MAX_OPTIONAL_INPUTS,
+            });
+        }
+
+        let mut outcomes: Vec<Outcome> = Vec::new();
+        for mask in 0..(1usize << optional_inputs.len()) {
+            let subset: Vec<&str> = optional_inputs
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, name)| *name)
+                .collect();
+
+            let mut active = self.active_ids();
+            let mut given = self.input_ids();
+            for id in subset.iter().filter_map(|n| self.symbols.get(n)) {
+                active.insert(id);
+                given.insert(id);
+            }
+
+            let mut conclusions: Vec<String> = self
+                .settle(active)
+                .into_iter()
+                .filter(|id| !given.contains(id))
+                .map(|id| self.label(id))
+                .collect();
+            conclusions.sort();
+
+            if outcomes.iter().all(|(_, seen)| *seen != conclusions) {
+                let subset = subset.iter().map(|n| n.to_string()).collect();
+                outcomes.push((subset, conclusions));
+            }
+        }
+        Ok(outcomes)
+    }
+
+    /// Counterfactual: what would become true if `extra_inputs` were injected
+    /// too. Settles a copy of the active set with `ponder`'s tick loop and
+    /// returns the newly active labels, sorted, without the hypothetical
+    /// inputs themselves. The mind itself is left untouched.
+    pub fn what_if(&self, extra_inputs: &[&str]) -> Vec<String> {
+        let real = self.active_ids();
+        let extra: FlagSet = extra_inputs
+            .iter()
+            .filter_map(|n| self.symbols.get(n))
+            .collect();
+
+        let mut gained: Vec<String> = self
+            .settle(real.union(&extra))
+            .into_iter()
+            .filter(|id| !real.contains(id) && !extra.contains(id))
+            .map(|id| self.label(id))
+            .collect();
+        gained.sort();
+        gained
+    }
+
+    /// Derived flags reachable from `inputs` within `k` ticks, i.e. the
+    /// conclusions affordable under a latency budget of `k` reasoning steps.
+    pub fn reachable_within(&self, inputs: &[&str], k: usize) -> Vec<String> {
+        let given: FlagSet = inputs.iter().filter_map(|n| self.symbols.get(n)).collect();
+
+        let mut active = given.clone();
+        for _ in 0..k {
+            let outputs = self.step(&active, None);
+            if outputs.is_empty() {
+                break;
+            }
+            active.extend(outputs);
+        }
+
+        let mut derived: Vec<String> = active.difference(&given).map(|id| self.label(id)).collect();
+        derived.sort();
+        derived
+    }
+
+    /// For each rule, how many conclusions reached from `inputs` would be lost
+    /// without it, most damaging first (ties by rule index).
+    ///
+    /// Runs one full fixpoint per rule, so keep it to offline analysis.
+    pub fn keystone_rules(&self, inputs: &[&str]) -> Vec<(usize, usize)> {
+        let given: FlagSet = inputs.iter().filter_map(|n| self.symbols.get(n)).collect();
+        let baseline = self.settle(given.clone());
+        let conclusions: Vec<FlagId> = baseline.difference(&given).collect();
+
+        let mut impact: Vec<(usize, usize)> = (0..self.rules.len())
+            .map(|index| {
+                let without = self.settle_without(given.clone(), Some(index));
+                let lost = conclusions
+                    .iter()
+                    .filter(|id| !without.contains(id))
+                    .count();
+                (index, lost)
+            })
+            .collect();
+        impact.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        impact
+    }
+
+    // ========================================================================
+    // Exclusivity (Negative Logic)
+    // ========================================================================
+
+    /// Declare that `a` and `b` must never be active together, e.g. `Safe`
+    /// and `Unsafe`. Nothing is enforced: `contradictions` reports violations.
+    pub fn declare_exclusive(&mut self, a: &str, b: &str) {
+        let pair = (self.id(a), self.id(b));
+        let known = self
+            .exclusive_pairs
+            .iter()
+            .any(|&(x, y)| (x, y) == pair || (y, x) == pair);
+        if !known {
+            self.exclusive_pairs.push(pair);
+        }
+    }
+
+    /// Declared exclusive pairs that are both active, in declaration order.
+    /// Check after `ponder`, and `trace` both sides to see why each holds.
+    pub fn contradictions(&self) -> Vec<(String, String)> {
+        self.exclusive_pairs
+            .iter()
+            .filter(|(a, b)| {
+                self.active_memory.contains_key(a) && self.active_memory.contains_key(b)
+            })
+            .map(|(a, b)| (self.label(*a), self.label(*b)))
+            .collect()
+    }
+
+    /// Make `tick` (and so `ponder` and the loops built on it) stop short
+    /// of a contradiction: a tick whose derivations or vote winners would
+    /// make both flags of a declared exclusive pair hold, at least one of
+    /// them new, commits nothing and returns `false`. `halted` then tells
+    /// the halt from a fixpoint. The rules stay queued, so the next tick
+    /// halts again until the inputs or rules change. Off by default.
+    pub fn set_halt_on_contradiction(&mut self, halt: bool) {
+        self.halt_on_contradiction = halt;
+    }
+
+    /// The contradiction the last tick halted on, if it did.
+    pub fn halted(&self) -> Option<&Contradiction> {
+        self.halted.as_ref()
+    }
+
+    /// The first exclusive pair that committing `facts` and the vote
+    /// `winners` would complete; see `set_halt_on_contradiction`.
+    pub(crate) fn contradiction_in(
+        &self,
+        tick: usize,
+        facts: &[Fired],
+        winners: &[FlagId],
+    ) -> Option<Contradiction> {
+        let new: HashSet<FlagId> = facts
+            .iter()
+            .map(|(id, ..)| *id)
+            .chain(winners.iter().copied())
+            .collect();
+        let holds = |id: &FlagId| new.contains(id) || self.active_memory.contains_key(id);
+        let &(a, b) = self
+            .exclusive_pairs
+            .iter()
+            .find(|(a, b)| (new.contains(a) || new.contains(b)) && holds(a) && holds(b))?;
+
+        let mut rules: Vec<usize> = facts
+            .iter()
+            .filter(|(id, ..)| *id == a || *id == b)
+            .filter_map(|(id, causes, ..)| self.deriving_rule_index(*id, causes))
+            .collect();
+        // A side already held: the rule behind its leading justification
+        for id in [a, b] {
+            if let Some(source @ Source::Derived { .. }) = self.active_memory.get(&id) {
+                rules.extend(self.deriving_rule_index(id, source.causes()));
+            }
+        }
+        rules.sort();
+        rules.dedup();
+        Some(Contradiction {
+            tick,
+            flags: (self.label(a), self.label(b)),
+            rules,
+        })
+    }
+
+    /// Structural reason why `a` and `b` never co-occur, checking both directions.
+    /// `None` means nothing in the topology keeps them apart; they merely
+    /// did not meet in the tested scenarios.
+    pub fn why_exclusive(&self, a: &str, b: &str) -> Option<ExclusivityReason> {
+        self.inhibition_chains(a, b)
+            .or_else(|| self.inhibition_chains(b, a))
+    }
+
+    /// Succeeds if every rule producing `suppressed` forbids a flag that
+    /// `by` derives on its own.
+    fn inhibition_chains(&self, by: &str, suppressed: &str) -> Option<ExclusivityReason> {
+        let by_id = self.symbols.get(by)?;
+        let suppressed_id = self.symbols.get(suppressed)?;
+
+        // Everything `by` implies alone, remembering one trigger that led there.
+        let mut parent: HashMap<FlagId, FlagId> = HashMap::new();
+        let mut reached = FlagSet::from([by_id]);
+        loop {
+            let fired: Vec<(FlagId, Option<FlagId>)> = self
+                .firing_rules(&reached)
+                .map(|rule| (rule.output, self.causes(rule, &reached).first().copied()))
+                .collect();
+            if fired.is_empty() {
+                break;
+            }
+            for (output, via) in fired {
+                // Rules with no triggers fire regardless of `by`: no parent.
+                if let Some(via) = via {
+                    parent.entry(output).or_insert(via);
+                }
+                reached.insert(output);
+            }
+        }
+
+        let producers: Vec<usize> = (0..self.rules.len())
+            .filter(|&i| self.rules[i].output == suppressed_id)
+            .collect();
+        if producers.is_empty() {
+            return None;
+        }
+
+        let mut chains = Vec::new();
+        for index in producers {
+            let blocker = self.rules[index]
+                .forbids
+                .iter()
+                .find(|f| **f == by_id || parent.contains_key(*f))?;
+
+            let mut chain = vec![*blocker];
+            while let Some(&up) = parent.get(chain.last().unwrap()) {
+                chain.push(up);
+            }
+            chain.reverse();
+            chains.push((index, chain.into_iter().map(|id| self.label(id)).collect()));
+        }
+
+        Some(ExclusivityReason {
+            suppressed: suppressed.to_string(),
+            by: by.to_string(),
+            chains,
+        })
+    }
+
+    // ========================================================================
+    // Robustness Analysis
+    // ========================================================================
+
+    /// Indices of the rules producing `id` whose conditions currently hold,
+    /// leaving out those in a switched-off group or behind a closed gate.
+    pub(crate) fn satisfied_rules(&self, id: FlagId) -> Vec<usize> {
+        let active = self.active_ids();
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(index, rule)| {
+                rule.output == id
+                    && self.rule_enabled(*index, &active)
+                    && self.satisfied(rule, &active)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// How many rules currently justify `id`.
+    pub fn support_count(&self, id: FlagId) -> usize {
+        self.satisfied_rules(id).len()
+    }
+
+    /// Derived flags held up by exactly one rule, paired with that rule's index.
+    /// Disabling the rule would collapse the conclusion (single point of failure).
+    pub fn single_support_conclusions(&self) -> Vec<(String, usize)> {
+        let mut brittle: Vec<(String, usize)> = self
+            .active_memory
+            .iter()
+            .filter(|(_, source)| matches!(source, Source::Derived { .. }))
+            .filter(|(id, _)| self.support_count(**id) == 1)
+            .map(|(id, _)| (self.label(*id), self.satisfied_rules(*id)[0]))
+            .collect();
+        brittle.sort();
+        brittle
+    }
+
+    /// Input axioms the recorded derivation of `goal` rests on.
+    pub fn supporting_inputs(&self, goal: &str) -> Vec<String> {
+        let mut found = HashSet::new();
+        let mut stack: Vec<FlagId> = self.symbols.get(goal).into_iter().collect();
+        while let Some(id) = stack.pop() {
+            match self.active_memory.get(&id) {
+                Some(Source::Input) => {
+                    found.insert(id);
+                }
+                Some(source @ Source::Derived { .. }) => stack.extend(source.causes()),
+                None => {}
+            }
+        }
+
+        let mut labels: Vec<String> = found.into_iter().map(|id| self.label(id)).collect();
+        labels.sort();
+        labels
+    }
+
+    /// Supporting inputs the goal does not actually need: with one removed
+    /// (on a copy of memory), the remaining inputs still derive the goal.
+    pub fn dominated_inputs(&self, goal: &str) -> Vec<String> {
+        let Some(goal_id) = self.symbols.get(goal) else {
+            return Vec::new();
+        };
+        let inputs = self.input_ids();
+
+        self.supporting_inputs(goal)
+            .into_iter()
+            .filter(|label| {
+                let mut rest = inputs.clone();
+                if let Some(removed) = self.symbols.get(label) {
+                    rest.remove(&removed);
+                }
+                self.settle(rest).contains(&goal_id)
+            })
+            .collect()
+    }
+
+    /// How much each input under `target` holds up its confidence: the drop
+    /// when that input alone is retracted on a copy of this mind and the
+    /// rest derived again, largest first (ties by label). Every input some
+    /// recorded justification rests on counts, not only the leading one's.
+    /// A drop of the whole confidence means `target` is lost without it.
+    ///
+    /// Drops in between need graded confidence (`inject_weighted`, fuzzy
+    /// rules); on crisp inputs this is `dominated_inputs` in numbers.
+    /// Empty if `target` is not active.
+    pub fn influence(&self, target: &str) -> Vec<(String, f32)>
+    where
+        T: Clone,
+    {
+        let Some(before) = self.confidence(target) else {
+            return Vec::new();
+        };
+        let mut inputs = BTreeSet::new();
+        let mut seen = HashSet::new();
+        let mut stack: Vec<FlagId> = self.symbols.get(target).into_iter().collect();
+        while let Some(id) = stack.pop() {
+            if !seen.insert(id) {
+                continue;
+            }
+            match self.active_memory.get(&id) {
+                Some(Source::Input) => {
+                    inputs.insert(self.label(id));
+                }
+                Some(Source::Derived { justifications }) => {
+                    stack.extend(justifications.iter().flatten());
+                }
+                None => {}
+            }
+        }
+
+        let mut drops: Vec<(String, f32)> = inputs
+            .into_iter()
+            .map(|label| {
+                // Re-derived from the other inputs: a conclusion surviving
+                // `retract` keeps the confidence it had.
+                let mut copy = self.clone();
+                copy.set_reporter(NullReporter);
+                copy.reset_derivations();
+                copy.retract(&[label.as_str()]);
+                copy.ponder();
+                let drop = before - copy.confidence(target).unwrap_or(0.0);
+                (label, drop)
+            })
+            .collect();
+        drops.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        drops
+    }
+
+    /// Minimal subsets of `candidate_inputs` that derive `goal` on their own,
+    /// other than the inputs the current derivation rests on. These are
+    /// backup paths worth securing; it proposes input sets, not rules.
+    ///
+    /// Tries every subset (2^n fixpoints), so `n` is capped at
+    /// `MAX_CANDIDATE_INPUTS`.
+    pub fn suggest_redundancy(
+        &self,
+        goal: &str,
+        candidate_inputs: &[&str],
+    ) -> Result<Vec<Vec<String>>, MindError> {
+        const MAX_CANDIDATE_INPUTS: usize = 16;
+        if candidate_inputs.len() > MAX_CANDIDATE_INPUTS {
+            return Err(MindError::TooManyInputs {
+                analysis: "suggest_redundancy",
+                given: candidate_inputs.len(),
+                cap: MAX_CANDIDATE_INPUTS,
+            });
+        }
+        let Some(goal_id) = self.symbols.get(goal) else {
+            return Ok(Vec::new());
+        };
+
+        let mut minimal: Vec<BTreeSet<&str>> = Vec::new();
+        for mask in 0..(1usize << candidate_inputs.len()) {
+            let subset: BTreeSet<&str> = candidate_inputs
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, name)| *name)
+                .collect();
+            if minimal.iter().any(|m| m.is_subset(&subset)) {
+                continue;
+            }
+
+            let active = subset.iter().filter_map(|n| self.symbols.get(n)).collect();
+            if self.settle(active).contains(&goal_id) {
+                minimal.retain(|m| !subset.is_subset(m));
+                minimal.push(subset);
+            }
+        }
+
+        let current = self.supporting_inputs(goal);
+        let mut suggestions: Vec<Vec<String>> = minimal
+            .into_iter()
+            .map(|set| set.into_iter().map(str::to_string).collect())
+            .filter(|set: &Vec<String>| *set != current)
+            .collect();
+        suggestions.sort();
+        Ok(suggestions)
+    }
+
+    /// Every minimal set of active inputs that derives `id`, following all
+    /// satisfied rules rather than only the recorded one, every custom rule
+    /// that fires, and the recorded causes of a vote winner (while those
+    /// hold; the rival candidates' absence is not part of the set). Each
+    /// input set is the fingerprint of one explanation; `path` guards
+    /// against circular rules.
+    fn minimal_explanations(&self, id: FlagId, path: &mut Vec<FlagId>) -> Vec<BTreeSet<FlagId>> {
+        match self.active_memory.get(&id) {
+            None => return Vec::new(),
+            Some(Source::Input) => return vec![BTreeSet::from([id])],
+            Some(Source::Derived { .. }) if path.contains(&id) => return Vec::new(),
+            Some(Source::Derived { .. }) => {}
+        }
+
+        path.push(id);
+        let mut found: Vec<BTreeSet<FlagId>> = Vec::new();
+        for index in self.satisfied_rules(id) {
+            // Combine one explanation per trigger (cartesian product);
+            // any `quorum` active members of the group are an alternative.
+            let rule = &self.rules[index];
+            let mut partial = vec![BTreeSet::new()];
+            for &trigger in &rule.triggers {
+                let options = self.minimal_explanations(trigger, path);
+                partial = partial
+                    .iter()
+                    .flat_map(|p| options.iter().map(move |o| p.union(o).copied().collect()))
+                    .collect();
+            }
+            if !rule.any_of.is_empty() {
+                // picked[c]: explanations of every way to pick c members so far.
+                let mut picked: Vec<Vec<BTreeSet<FlagId>>> = vec![Vec::new(); rule.quorum + 1];
+                picked[0].push(BTreeSet::new());
+                for &member in &rule.any_of {
+                    let member_options = self.minimal_explanations(member, path);
+                    for c in (0..rule.quorum).rev() {
+                        let grown: Vec<BTreeSet<FlagId>> = picked[c]
+                            .iter()
+                            .flat_map(|p| {
+                                member_options
+                                    .iter()
+                                    .map(move |o| p.union(o).copied().collect())
+                            })
+                            .collect();
+                        picked[c + 1].extend(grown);
+                    }
+                }
+                let options = core::mem::take(&mut picked[rule.quorum]);
+                partial = partial
+                    .iter()
+                    .flat_map(|p| options.iter().map(move |o| p.union(o).copied().collect()))
+                    .collect();
+            }
+            found.extend(partial);
+        }
+
+        let active = self.active_ids();
+        let mut alternatives: Vec<Vec<FlagId>> = self
+            .custom_fired(&active)
+            .into_iter()
+            .filter(|(output, _)| *output == id)
+            .map(|(_, causes)| causes)
+            .collect();
+        let voted = self
+            .votes
+            .iter()
+            .any(|vote| vote.candidates.iter().any(|(out, _)| *out == id));
+        if voted && let Some(Source::Derived { justifications }) = self.active_memory.get(&id) {
+            alternatives.extend(
+                justifications
+                    .iter()
+                    .filter(|causes| causes.iter().all(|c| active.contains(c)))
+                    .cloned(),
+            );
+        }
+        for causes in alternatives {
+            let mut partial = vec![BTreeSet::new()];
+            for cause in causes {
+                let options = self.minimal_explanations(cause, path);
+                partial = partial
+                    .iter()
+                    .flat_map(|p| options.iter().map(move |o| p.union(o).copied().collect()))
+                    .collect();
+            }
+            found.extend(partial);
+        }
+        path.pop();
+
+        found.sort();
+        found.dedup();
+        found
+            .iter()
+            .filter(|a| !found.iter().any(|b| b != *a && b.is_subset(a)))
+            .cloned()
+            .collect()
+    }
+
+    /// Number of structurally distinct minimal explanations for `goal`,
+    /// through rules, custom rules and votes alike.
+    /// 1 means fragile single-path reasoning; higher means resilient.
+    pub fn explanation_diversity(&self, goal: &str) -> usize {
+        self.symbols
+            .get(goal)
+            .map_or(0, |id| self.minimal_explanations(id, &mut Vec::new()).len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decay::TriggerMode;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    #[test]
+    fn circular_rules_are_reported_as_a_cycle() {
+        let mut mind = quiet();
+        mind.learn(&["Seed"], "A");
+        mind.learn(&["A"], "B");
+        mind.learn(&["B"], "C");
+        mind.learn(&["C"], "A");
+        let cycle = mind.has_cycles().expect("A -> B -> C -> A is a loop");
+        let mut labels: Vec<String> = cycle.iter().map(|&id| mind.label(id)).collect();
+        labels.sort();
+        assert_eq!(labels, ["A", "B", "C"]);
+    }
+
+    #[test]
+    fn a_chain_has_no_cycle() {
+        let mut mind = quiet();
+        mind.learn(&["Seed"], "A");
+        mind.learn(&["A"], "B");
+        mind.learn(&["A", "B"], "C");
+        assert_eq!(mind.has_cycles(), None);
+    }
+
+    #[test]
+    fn exclusive_flags_without_inhibition_contradict() {
+        let mut mind = quiet();
+        mind.learn(&["Guard"], "Safe");
+        mind.learn(&["Fire"], "Unsafe");
+        mind.declare_exclusive("Safe", "Unsafe");
+        mind.declare_exclusive("Unsafe", "Safe");
+        mind.inject(&["Guard", "Fire"]);
+        mind.ponder();
+        assert_eq!(
+            mind.contradictions(),
+            [("Safe".to_string(), "Unsafe".to_string())]
+        );
+        assert!(mind.why_exclusive("Safe", "Unsafe").is_none());
+    }
+
+    #[test]
+    fn inhibition_keeps_exclusive_flags_apart() {
+        let mut mind = quiet();
+        mind.rule(&["Guard"], &["Unsafe"], "Safe");
+        mind.learn(&["Fire"], "Unsafe");
+        mind.declare_exclusive("Safe", "Unsafe");
+        mind.inject(&["Guard", "Fire"]);
+        mind.ponder();
+        assert!(mind.contradictions().is_empty());
+        assert!(mind.why_exclusive("Safe", "Unsafe").is_some());
+    }
+
+    #[test]
+    fn a_misspelled_trigger_makes_a_dead_rule() {
+        let mut mind = quiet();
+        mind.declare_inputs(&["Motion", "Night"]);
+        mind.learn(&["Motion", "Night"], "Lamp");
+        mind.learn(&["Motoin"], "Alarm");
+        mind.learn(&["Alarm"], "Siren");
+        assert_eq!(mind.dead_rules(), [1, 2]);
+        assert_eq!(mind.orphan_triggers(), ["Motoin"]);
+
+        // Without declared inputs the typo passes for a sensor
+        let mut open = quiet();
+        open.learn(&["Motoin"], "Alarm");
+        assert!(open.dead_rules().is_empty());
+    }
+
+    #[test]
+    fn a_rule_always_inhibited_first_is_dead() {
+        let mut mind = quiet();
+        mind.learn(&["Smoke"], "Alarm");
+        mind.rule(&["Smoke"], &["Alarm"], "AllClear"); // Alarm's stratum settles first
+        mind.learn(&["Smoke", "Fan"], "Vent");
+        assert!(mind.dead_rules().is_empty());
+        assert_eq!(mind.always_dead_rules(&["Smoke", "Fan"]).unwrap(), [1]);
+    }
+
+    #[test]
+    fn a_rule_forbidding_its_own_trigger_is_dead() {
+        let mut mind = quiet();
+        mind.learn(&["Smoke"], "Alarm");
+        mind.rule(&["Smoke"], &["Smoke"], "Paradox");
+        assert_eq!(mind.always_dead_rules(&["Smoke"]).unwrap(), [1]);
+    }
+
+    #[test]
+    fn outcomes_are_listed_once_per_distinct_result() {
+        let mut mind = quiet();
+        mind.rule(&["SwitchOn"], &["PowerOutage"], "LightOn");
+        let outcomes = mind
+            .enumerate_outcomes(&["SwitchOn", "PowerOutage"])
+            .unwrap();
+        let light = (vec!["SwitchOn".to_string()], vec!["LightOn".to_string()]);
+        assert_eq!(outcomes, [(vec![], vec![]), light]);
+    }
+
+    #[test]
+    fn reach_and_keystones_follow_the_chain() {
+        let mut mind = quiet();
+        mind.learn(&["KeyCard", "Fingerprint"], "AccessGranted");
+        mind.learn(&["AccessGranted"], "DoorOpen");
+        mind.learn(&["Smoke"], "Alarm");
+        let inputs = ["KeyCard", "Fingerprint", "Smoke"];
+        assert_eq!(
+            mind.reachable_within(&inputs, 1),
+            ["AccessGranted", "Alarm"]
+        );
+        assert_eq!(
+            mind.reachable_within(&inputs, 2),
+            ["AccessGranted", "Alarm", "DoorOpen"]
+        );
+        // Without rule 0 neither AccessGranted nor DoorOpen is reached
+        assert_eq!(mind.keystone_rules(&inputs), [(0, 2), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn a_narrower_rule_with_the_same_output_is_redundant() {
+        let mut mind = quiet();
+        mind.learn(&["Smoke"], "Alarm");
+        mind.learn(&["Smoke", "Heat"], "Alarm");
+        // Suppressed by a drill: it does not cover `Smoke + Heat`
+        mind.rule(&["Heat"], &["Drill"], "Alarm");
+        assert_eq!(mind.redundant_rules(), [(1, 0)]);
+    }
+
+    #[test]
+    fn a_rule_in_another_group_is_not_redundant() {
+        let mut mind = quiet();
+        mind.learn_in_group("night", &["Motion"], "Alarm");
+        mind.learn(&["Motion", "Door"], "Alarm");
+        mind.learn_in_group("day", &["Motion", "Door"], "Alarm");
+        mind.learn_in_group("night", &["Motion", "Window"], "Alarm");
+        assert_eq!(mind.redundant_rules(), [(3, 0)]);
+    }
+
+    #[test]
+    fn lints_flag_produced_inputs_and_unread_outputs() {
+        let mut mind = quiet();
+        mind.learn(&["Smoke"], "Alarm");
+        mind.learn(&["Alarm"], "Siren");
+        mind.learn(&["Heat"], "Smoke");
+        assert_eq!(mind.check_io_separation(&["Smoke", "Heat"]), ["Smoke"]);
+        assert_eq!(mind.unused_outputs(), ["Siren"]);
+        mind.declare_outputs(&["Siren"]);
+        assert!(mind.unused_outputs().is_empty());
+    }
+
+    #[test]
+    fn specialize_keeps_custom_rules() {
+        let mut mind = quiet();
+        mind.learn(&["Mains", "Switch"], "Lamp");
+        mind.rule_custom(&["Lamp", "Night"], "Glow", |on| on[0] && on[1]);
+        let mut lamp = mind.specialize(&["Mains"]);
+        lamp.set_reporter(NullReporter);
+        lamp.inject(&["Switch", "Night"]);
+        lamp.ponder();
+        assert!(lamp.is_active("Glow"));
+    }
+
+    #[test]
+    fn specialize_concludes_what_the_full_mind_does() {
+        let mut mind = quiet();
+        mind.set_activation_threshold(0.4);
+        mind.set_trigger_mode(TriggerMode::Latched);
+        mind.declare_exclusive("Open", "Locked");
+        mind.declare_inputs(&["Mains", "Key", "Card", "Alarm"]);
+        mind.learn(&["Mains", "Key"], "Open");
+        mind.rule(&["Mains", "Card"], &["Alarm"], "Open");
+        mind.learn(&["Alarm"], "Locked");
+        mind.learn(&["Mains"], "Lit");
+        mind.learn(&["Lit", "Open"], "Welcome");
+        let mut door = mind.specialize(&["Mains"]);
+        door.set_reporter(NullReporter);
+        assert_eq!(door.activation_threshold, 0.4);
+        assert_eq!(door.trigger_mode(), TriggerMode::Latched);
+        assert_eq!(door.exclusive_pairs, mind.exclusive_pairs);
+        assert_eq!(door.declared_inputs, mind.declared_inputs);
+
+        let scenarios: [&[&str]; 5] = [
+            &[],
+            &["Key"],
+            &["Card"],
+            &["Card", "Alarm"],
+            &["Key", "Alarm"],
+        ];
+        for inputs in scenarios {
+            let mut full = mind.clone();
+            full.reset_memory();
+            full.inject(&["Mains"]);
+            full.inject(inputs);
+            full.ponder();
+            door.reset_memory();
+            door.inject(inputs);
+            door.ponder();
+            for goal in ["Open", "Locked", "Welcome"] {
+                assert_eq!(
+                    full.is_active(goal),
+                    door.is_active(goal),
+                    "{goal} on {inputs:?}"
+                );
+            }
+            assert_eq!(full.contradictions(), door.contradictions(), "{inputs:?}");
+        }
+    }
+
+    #[test]
+    fn halting_stops_before_a_contradiction() {
+        let mut mind = quiet();
+        mind.learn(&["Guard"], "Safe");
+        mind.learn(&["Fire"], "Smoke");
+        mind.learn(&["Smoke"], "Unsafe");
+        mind.learn(&["Unsafe"], "Evacuate");
+        mind.declare_exclusive("Safe", "Unsafe");
+        mind.set_halt_on_contradiction(true);
+        mind.inject(&["Guard", "Fire"]);
+        mind.ponder();
+
+        let halt = mind.halted().expect("tick 2 completes the pair").clone();
+        assert_eq!(halt.tick, 2);
+        assert_eq!(halt.flags, ("Safe".to_string(), "Unsafe".to_string()));
+        assert_eq!(halt.rules, [0, 2]);
+        assert!(mind.is_active("Smoke"));
+        assert!(!mind.is_active("Unsafe"));
+        assert!(!mind.is_active("Evacuate"));
+        assert!(mind.contradictions().is_empty());
+
+        // Still queued: the next tick halts again, until the inputs change
+        assert!(!mind.tick(3));
+        assert_eq!(mind.halted().map(|h| &h.flags), Some(&halt.flags));
+        mind.retract(&["Guard"]);
+        mind.ponder();
+        assert!(mind.halted().is_none());
+        assert!(mind.is_active("Evacuate"));
+    }
+
+    #[test]
+    fn without_halting_the_contradiction_is_committed() {
+        let mut mind = quiet();
+        mind.learn(&["Guard"], "Safe");
+        mind.learn(&["Fire"], "Unsafe");
+        mind.declare_exclusive("Safe", "Unsafe");
+        mind.inject(&["Guard", "Fire"]);
+        mind.ponder();
+        assert!(mind.halted().is_none());
+        assert_eq!(mind.contradictions().len(), 1);
+    }
+
+    #[test]
+    fn support_leaves_out_rules_that_cannot_take_part() {
+        let mut mind = quiet();
+        mind.learn(&["Motion"], "Alarm");
+        mind.learn_in_group("night", &["Motion"], "Alarm");
+        mind.learn_in_group("away", &["Motion"], "Alarm");
+        mind.gate_group("away", "Vacation");
+        mind.inject(&["Motion"]);
+        mind.ponder();
+        let alarm = mind.id("Alarm");
+        assert_eq!(mind.support_count(alarm), 2);
+
+        mind.set_group_enabled("night", false);
+        assert_eq!(mind.support_count(alarm), 1);
+        assert_eq!(mind.single_support_conclusions(), [("Alarm".into(), 0)]);
+
+        mind.inject(&["Vacation"]);
+        assert_eq!(mind.support_count(alarm), 2);
+    }
+
+    #[test]
+    fn diversity_counts_votes_and_custom_rules() {
+        let mut mind = quiet();
+        mind.learn_vote(&[
+            ("Cat", &[("Whiskers", 0.5), ("Meows", 0.9)]),
+            ("Dog", &[("Whiskers", 0.3), ("Barks", 0.9)]),
+        ]);
+        mind.rule_custom(&["Fetch", "Stick"], "Dog", |seen| seen[0] && seen[1]);
+        mind.inject(&["Whiskers", "Barks"]);
+        mind.ponder();
+        assert!(mind.is_active("Dog"));
+        assert_eq!(mind.explanation_diversity("Dog"), 1);
+
+        mind.inject(&["Fetch", "Stick"]);
+        mind.ponder();
+        assert_eq!(mind.explanation_diversity("Dog"), 2);
+    }
+
+    #[test]
+    fn influence_ranks_inputs_by_confidence_drop() {
+        let mut mind = quiet();
+        mind.learn(&["Radar"], "Ship");
+        mind.learn(&["Sonar", "Calm"], "Ship");
+        mind.inject_weighted(&[("Radar", 0.9), ("Sonar", 0.6)]);
+        mind.inject(&["Calm"]);
+        mind.ponder();
+        assert_eq!(mind.confidence("Ship"), Some(0.9));
+
+        let influence = mind.influence("Ship");
+        let labels: Vec<&str> = influence.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(labels, ["Radar", "Calm", "Sonar"]);
+        assert!((influence[0].1 - 0.3).abs() < 1e-6);
+        assert_eq!(influence[1].1, 0.0);
+        assert_eq!(influence[2].1, 0.0);
+        assert_eq!(mind.confidence("Ship"), Some(0.9)); // Copies only
+
+        let mut crisp = quiet();
+        crisp.learn(&["Key"], "Open");
+        crisp.inject(&["Key"]);
+        crisp.ponder();
+        assert_eq!(crisp.influence("Open"), [("Key".to_string(), 1.0)]);
+        assert!(crisp.influence("Closed").is_empty());
+    }
+}
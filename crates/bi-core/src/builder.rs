@@ -0,0 +1,117 @@
+//! Fluent construction of a `Mind`, checked before it is handed out.
+//!
+//! ```
+//! use bi_core::MindBuilder;
+//!
+//! let mut mind = MindBuilder::new()
+//!     .rule(&["Knife"], "Sharp")
+//!     .inhibited(&["Apple"], &["Rotten"], "Edible")
+//!     .input("Knife")
+//!     .build()
+//!     .expect("no rule triggers itself");
+//! mind.ponder();
+//! assert!(mind.is_active("Sharp"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::engine::Mind;
+
+/// Why `MindBuilder::build` refused the knowledge it was given.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildError {
+    /// The rule at `rule` (in declaration order) lists its output among its
+    /// own triggers, so it could only ever fire on what it already knows.
+    SelfTrigger { rule: usize, label: String },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::SelfTrigger { rule, label } => {
+                write!(f, "rule #{}: `{}` triggers itself", rule, label)
+            }
+        }
+    }
+}
+
+impl core::error::Error for BuildError {}
+
+/// Collects rules and initial inputs, then builds the mind in one step.
+/// Nothing is interned or learned until `build`.
+#[derive(Clone, Debug, Default)]
+pub struct MindBuilder {
+    rules: Vec<(Vec<String>, Vec<String>, String)>, // (triggers, forbids, output)
+    inputs: Vec<String>,
+    sorted_ids: bool,
+}
+
+impl MindBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A plain AND rule: A + B -> C (see `Mind::learn`).
+    pub fn rule(self, triggers: &[&str], output: &str) -> Self {
+        self.inhibited(triggers, &[], output)
+    }
+
+    /// A rule with forbids: (A + B) - (C) -> D (see `Mind::rule`).
+    pub fn inhibited(mut self, triggers: &[&str], forbids: &[&str], output: &str) -> Self {
+        let owned = |labels: &[&str]| labels.iter().map(|l| l.to_string()).collect();
+        self.rules
+            .push((owned(triggers), owned(forbids), output.to_string()));
+        self
+    }
+
+    /// A flag to be active from the start, silently (no `[Input]` line).
+    pub fn input(mut self, label: &str) -> Self {
+        self.inputs.push(label.to_string());
+        self
+    }
+
+    /// Assign ids in sorted label order rather than first-seen order (see
+    /// `Mind::freeze_labels`), so reordering the rules keeps every id.
+    pub fn sorted_ids(mut self) -> Self {
+        self.sorted_ids = true;
+        self
+    }
+
+    /// The mind with every rule learned in order and every input injected,
+    /// ready to `ponder`.
+    pub fn build(self) -> Result<Mind, BuildError> {
+        for (index, (triggers, _, output)) in self.rules.iter().enumerate() {
+            if triggers.contains(output) {
+                return Err(BuildError::SelfTrigger {
+                    rule: index,
+                    label: output.clone(),
+                });
+            }
+        }
+
+        let mut mind = Mind::new();
+        if self.sorted_ids {
+            let labels: Vec<&str> = self
+                .rules
+                .iter()
+                .flat_map(|(triggers, forbids, output)| {
+                    triggers.iter().chain(forbids).chain([output])
+                })
+                .chain(&self.inputs)
+                .map(String::as_str)
+                .collect();
+            let _ = mind.freeze_labels(&labels); // A fresh mind has no ids yet
+        }
+        for (triggers, forbids, output) in &self.rules {
+            let triggers: Vec<&str> = triggers.iter().map(String::as_str).collect();
+            let forbids: Vec<&str> = forbids.iter().map(String::as_str).collect();
+            mind.rule(&triggers, &forbids, output);
+        }
+        for label in &self.inputs {
+            mind.inject_one(label, 1.0);
+        }
+        Ok(mind)
+    }
+}
@@ -0,0 +1,157 @@
+//! Rule-Base Checks: inference cases that fail loudly.
+//!
+//! ```
+//! use bi_core::{Mind, assert_derives};
+//!
+//! let mut mind = Mind::new();
+//! mind.learn(&["Knife"], "Sharp");
+//! mind.rule(&["SwitchOn"], &["PowerOutage"], "LightOn");
+//!
+//! assert_derives!(mind, ["Knife", "SwitchOn"] => ["Sharp", "LightOn"]);
+//! assert_derives!(mind, ["SwitchOn", "PowerOutage"] => [], not ["LightOn"]);
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::engine::Mind;
+use crate::report::NullReporter;
+
+/// One inference case: inject `inputs` into a copy of a mind with empty
+/// memory, ponder silently, then check which flags hold.
+#[derive(Clone, Debug, Default)]
+pub struct TestCase {
+    inputs: Vec<String>,
+    expected: Vec<String>,
+    absent: Vec<String>,
+}
+
+/// A `TestCase` that did not come out as expected; labels sorted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaseFailure {
+    pub inputs: Vec<String>,
+    pub missing: Vec<String>,    // Expected, but not active
+    pub unexpected: Vec<String>, // Expected absent, but active
+    pub active: Vec<String>,     // Everything that held at the fixpoint
+}
+
+impl fmt::Display for CaseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quoted = |labels: &[String]| {
+            labels
+                .iter()
+                .map(|l| format!("`{}`", l))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(f, "inputs {}:", quoted(&self.inputs))?;
+        if !self.missing.is_empty() {
+            write!(f, " missing {};", quoted(&self.missing))?;
+        }
+        if !self.unexpected.is_empty() {
+            write!(f, " unexpectedly active {};", quoted(&self.unexpected))?;
+        }
+        write!(f, " active were {}", quoted(&self.active))
+    }
+}
+
+impl core::error::Error for CaseFailure {}
+
+impl TestCase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn inputs(mut self, labels: &[&str]) -> Self {
+        self.inputs.extend(labels.iter().map(|l| l.to_string()));
+        self
+    }
+
+    /// Flags that must be active at the fixpoint.
+    pub fn expect(mut self, labels: &[&str]) -> Self {
+        self.expected.extend(labels.iter().map(|l| l.to_string()));
+        self
+    }
+
+    /// Flags that must not be active at the fixpoint.
+    pub fn absent(mut self, labels: &[&str]) -> Self {
+        self.absent.extend(labels.iter().map(|l| l.to_string()));
+        self
+    }
+
+    /// Runs the case on a copy of `mind`: same rules and labels, empty
+    /// memory, no output. `mind` itself is left as it was.
+    pub fn run<T: Clone>(&self, mind: &Mind<T>) -> Result<(), CaseFailure> {
+        let mut copy = mind.clone();
+        copy.set_reporter(NullReporter);
+        copy.reset_memory();
+        let inputs: Vec<&str> = self.inputs.iter().map(String::as_str).collect();
+        copy.inject(&inputs);
+        copy.ponder();
+
+        let sorted = |labels: &[String], keep: &dyn Fn(&str) -> bool| {
+            let mut labels: Vec<String> = labels.iter().filter(|l| keep(l)).cloned().collect();
+            labels.sort();
+            labels
+        };
+        let missing = sorted(&self.expected, &|l| !copy.is_active(l));
+        let unexpected = sorted(&self.absent, &|l| copy.is_active(l));
+        if missing.is_empty() && unexpected.is_empty() {
+            return Ok(());
+        }
+        Err(CaseFailure {
+            inputs: self.inputs.clone(),
+            missing,
+            unexpected,
+            active: copy.active_labels(),
+        })
+    }
+}
+
+/// Panics unless `mind`, fed `[inputs]` from empty memory, derives every
+/// label of `[expected]` and, after `not`, none of `[absent]`. The panic
+/// message lists what was missing, what should not hold, and what did.
+/// See `TestCase` for the non-panicking form.
+#[macro_export]
+macro_rules! assert_derives {
+    ($mind:expr, [$($input:expr),* $(,)?] => [$($expected:expr),* $(,)?]
+        $(, not [$($absent:expr),* $(,)?])? $(,)?) => {
+        if let Err(failure) = $crate::TestCase::new()
+            .inputs(&[$($input),*])
+            .expect(&[$($expected),*])
+            $(.absent(&[$($absent),*]))?
+            .run(&$mind)
+        {
+            panic!("assert_derives! failed: {}", failure);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_wrong_expectation_lists_what_went_wrong() {
+        let mut kettle = Mind::new();
+        kettle.learn(&["Water", "Heat"], "Boiling");
+        kettle.rule(&["Boiling"], &["LidOff"], "Whistle");
+
+        let check = TestCase::new()
+            .inputs(&["Water", "LidOff"])
+            .expect(&["Boiling"])
+            .absent(&["LidOff"]);
+        let failure = check.run(&kettle).unwrap_err();
+        assert_eq!(failure.missing, ["Boiling"]);
+        assert_eq!(failure.unexpected, ["LidOff"]);
+        assert_eq!(failure.active, ["LidOff", "Water"]);
+        assert_eq!(
+            failure.to_string(),
+            "inputs `Water`, `LidOff`: missing `Boiling`; unexpectedly active `LidOff`; \
+             active were `LidOff`, `Water`"
+        );
+        assert!(kettle.active_labels().is_empty()); // Ran on a copy
+    }
+}
@@ -0,0 +1,86 @@
+//! Simulation Clock: stimuli scheduled as discrete events.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::engine::Mind;
+
+/// A stimulus change queued on the simulation clock.
+#[derive(Clone, Debug)]
+pub(crate) enum Scheduled {
+    Inject(Vec<String>),
+    Retract(Vec<String>),
+}
+
+impl<T> Mind<T> {
+    /// Queue inputs to be injected when the clock reaches `at_tick`.
+    /// A time the clock has already passed means the next one it runs.
+    pub fn schedule(&mut self, at_tick: usize, inputs: &[&str]) {
+        let labels = inputs.iter().map(|n| n.to_string()).collect();
+        self.enqueue(at_tick, Scheduled::Inject(labels));
+    }
+
+    /// Queue inputs to be retracted when the clock reaches `at_tick`.
+    /// A time the clock has already passed means the next one it runs.
+    pub fn schedule_retract(&mut self, at_tick: usize, inputs: &[&str]) {
+        let labels = inputs.iter().map(|n| n.to_string()).collect();
+        self.enqueue(at_tick, Scheduled::Retract(labels));
+    }
+
+    /// Queues `event` at `at_tick`, or at the current time if that passed,
+    /// after the events already queued there.
+    fn enqueue(&mut self, at_tick: usize, event: Scheduled) {
+        self.schedule
+            .entry(at_tick.max(self.clock))
+            .or_default()
+            .push(event);
+    }
+
+    /// Advances the clock up to and including `until`, applying each
+    /// scheduled event at its time and pondering after it.
+    /// Times without events are skipped silently (memory is already stable).
+    pub fn run_clock(&mut self, until: usize) {
+        while self.clock <= until {
+            if let Some(events) = self.schedule.remove(&self.clock) {
+                let time = self.clock;
+                self.report(|r| r.clock(time));
+                for event in events {
+                    match event {
+                        Scheduled::Inject(labels) => {
+                            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+                            self.inject(&labels);
+                            self.ponder();
+                        }
+                        Scheduled::Retract(labels) => {
+                            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+                            self.retract(&labels);
+                        }
+                    }
+                }
+            }
+            self.clock += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    #[test]
+    fn an_event_scheduled_in_the_past_runs_next() {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind.learn(&["Smoke"], "Alarm");
+        mind.schedule(2, &["Smoke"]);
+        mind.run_clock(5);
+        assert!(mind.is_active("Alarm"));
+
+        mind.schedule_retract(3, &["Smoke"]);
+        assert!(mind.is_active("Alarm"));
+        mind.run_clock(6);
+        assert!(!mind.is_active("Alarm"));
+        assert!(mind.schedule.is_empty());
+    }
+}
@@ -0,0 +1,9 @@
+//! The hashed collections of the crate: std's with the `std` feature,
+//! hashbrown's without, so the same code builds on `alloc` alone.
+
+pub(crate) use alloc::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap, HashSet, hash_map::RandomState as HashState};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{HashMap, HashSet, hash_map::DefaultHashBuilder as HashState};
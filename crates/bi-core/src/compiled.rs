@@ -0,0 +1,160 @@
+//! Compiled Plans: the rule base flattened for evaluation.
+//!
+//! `tick` follows each rule's own trigger, group and forbid vectors. A
+//! plan lays every rule's flags out in one array, with offsets and the
+//! scalar settings alongside, so evaluation walks contiguous slices.
+//! Results are identical; only the memory layout differs.
+
+use alloc::vec::Vec;
+
+use crate::engine::{Fired, Mind};
+use crate::flagset::FlagSet;
+use crate::symbols::FlagId;
+
+/// The rule base in evaluation order (see `Mind::compile`). Built from
+/// the rules as they were then, and dropped whenever one is added or
+/// removed, so a plan always matches the rules it was built from.
+#[derive(Clone, Debug, Default)]
+pub struct CompiledPlan {
+    // Rule i: triggers `bounds[3i]..bounds[3i+1]` of `flags`, then its
+    // group up to `bounds[3i+2]`, then its forbids up to `bounds[3i+3]`.
+    flags: Vec<FlagId>,
+    bounds: Vec<usize>,
+    outputs: Vec<FlagId>,
+    settings: Vec<Settings>,
+}
+
+/// The scalar fields of one rule that evaluation reads.
+#[derive(Clone, Copy, Debug)]
+struct Settings {
+    quorum: usize,
+    exclusive: bool,
+    strict: bool,
+    min_confidence: f32,
+    weight: f32,
+}
+
+impl CompiledPlan {
+    /// Number of rules in the plan.
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    /// Triggers, group members and forbids of the rule at `index`.
+    fn parts(&self, index: usize) -> (&[FlagId], &[FlagId], &[FlagId]) {
+        let at = &self.bounds[3 * index..3 * index + 4];
+        (
+            &self.flags[at[0]..at[1]],
+            &self.flags[at[1]..at[2]],
+            &self.flags[at[2]..at[3]],
+        )
+    }
+}
+
+impl<T> Mind<T> {
+    /// Builds the plan `tick_compiled` evaluates, unless the current one is
+    /// still valid. Adding or removing rules drops it; `tick_compiled`
+    /// then compiles it again on the next call, so calling this is only
+    /// needed to pay the cost up front.
+    pub fn compile(&mut self) -> &CompiledPlan {
+        self.plan.get_or_insert_with(|| {
+            let mut plan = CompiledPlan::default();
+            plan.bounds.push(0);
+            for rule in &self.rules {
+                for part in [&rule.triggers, &rule.any_of, &rule.forbids] {
+                    plan.flags.extend(part);
+                    plan.bounds.push(plan.flags.len());
+                }
+                plan.outputs.push(rule.output);
+                plan.settings.push(Settings {
+                    quorum: rule.quorum,
+                    exclusive: rule.exclusive,
+                    strict: rule.strict,
+                    min_confidence: rule.min_confidence,
+                    weight: rule.weight,
+                });
+            }
+            plan
+        })
+    }
+
+    /// `tick`, with the rules evaluated from the compiled plan (built first
+    /// if the rules changed). Candidates, order, log and memory are the
+    /// same as `tick`'s; the plan is only a faster layout to scan.
+    pub fn tick_compiled(&mut self, tick_count: usize) -> bool {
+        self.compile();
+        self.tick_using(tick_count, Self::evaluate_compiled)
+    }
+
+    /// `ponder` on top of `tick_compiled`.
+    pub fn ponder_compiled(&mut self) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("ponder_compiled").entered();
+        let mut tick = 1;
+        while self.tick_compiled(tick) {
+            tick += 1;
+        }
+        self.report(|r| r.settled());
+    }
+
+    /// `evaluate` over the plan: same checks, in the same order.
+    fn evaluate_compiled(&self, level: &[usize], reached: &FlagSet) -> Vec<Fired> {
+        let Some(plan) = &self.plan else {
+            return self.evaluate(level, reached);
+        };
+        let strength = |id: &FlagId| self.strength_of(*id);
+        let mut fired = Vec::new();
+        for &index in level {
+            let (triggers, any_of, forbids) = plan.parts(index);
+            let settings = plan.settings[index];
+
+            // AND, then OR / K-of-N / XOR
+            if !triggers.iter().all(|t| reached.contains(t)) {
+                continue;
+            }
+            let mut members: Vec<FlagId> = any_of
+                .iter()
+                .copied()
+                .filter(|a| reached.contains(a))
+                .collect();
+            if !any_of.is_empty()
+                && (members.len() < settings.quorum || (settings.exclusive && members.len() > 1))
+            {
+                continue;
+            }
+            // A strict rule needs every forbid settled
+            if settings.strict
+                && !forbids
+                    .iter()
+                    .all(|f| reached.contains(f) || self.known_false.contains(f))
+            {
+                continue;
+            }
+
+            members.sort_by(|a, b| strength(b).total_cmp(&strength(a)));
+            let mut causes = triggers.to_vec();
+            causes.extend(
+                self.gate(&self.rules[index])
+                    .filter(|gate| !causes.contains(gate)),
+            );
+            causes.extend(members.into_iter().take(settings.quorum));
+
+            let support = causes.iter().map(strength).fold(1.0, f32::min);
+            let inhibition = forbids
+                .iter()
+                .filter(|f| reached.contains(f))
+                .map(strength)
+                .fold(0.0, f32::max);
+            let value = support * (1.0 - inhibition);
+            if self.clears_threshold(plan.outputs[index], value) && value >= settings.min_confidence
+            {
+                fired.push((plan.outputs[index], causes, value, settings.weight));
+            }
+        }
+        fired
+    }
+}
@@ -0,0 +1,105 @@
+//! Custom Gates: rules whose condition is a closure.
+//!
+//! For logic the declared rules cannot express, a gate reads the presence
+//! of the flags it lists and decides whether its output fires. Gates run
+//! in `tick` after the declared rules, and in `settle`, truth maintenance
+//! and `trace`; the static analyses (`strata`, `prove`, `why_not`, ...)
+//! and `export_rules` see only declared rules.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::engine::Mind;
+use crate::flagset::FlagSet;
+use crate::symbols::FlagId;
+
+type Gate = Arc<dyn Fn(&[bool]) -> bool + Send + Sync>;
+
+/// `inputs` -> output whenever `gate` says so.
+#[derive(Clone)]
+pub(crate) struct CustomRule {
+    pub(crate) inputs: Vec<FlagId>,
+    pub(crate) output: FlagId,
+    gate: Gate, // Shared, so cloned minds keep their custom rules
+}
+
+impl fmt::Debug for CustomRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomRule")
+            .field("inputs", &self.inputs)
+            .field("output", &self.output)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CustomRule {
+    /// The same gate over other flags, e.g. those of a merged mind.
+    pub(crate) fn relabeled(&self, inputs: Vec<FlagId>, output: FlagId) -> CustomRule {
+        CustomRule {
+            inputs,
+            output,
+            gate: Arc::clone(&self.gate),
+        }
+    }
+
+    /// Whether both read the same flags into the same gate for the same output.
+    pub(crate) fn same_rule(&self, other: &CustomRule) -> bool {
+        self.inputs == other.inputs
+            && self.output == other.output
+            && Arc::ptr_eq(&self.gate, &other.gate)
+    }
+
+    /// Whether the gate opens against `active`; if so, the listed inputs
+    /// that are present, which become the causes.
+    fn fire(&self, active: &FlagSet) -> Option<Vec<FlagId>> {
+        let present: Vec<bool> = self.inputs.iter().map(|id| active.contains(id)).collect();
+        (self.gate)(&present).then(|| {
+            self.inputs
+                .iter()
+                .copied()
+                .filter(|id| active.contains(id))
+                .collect()
+        })
+    }
+}
+
+impl<T> Mind<T> {
+    /// Learn `output` under arbitrary boolean logic: each tick, `gate` gets
+    /// whether each of `inputs` is active, in the order listed, and says
+    /// whether `output` fires. The present inputs are its causes.
+    ///
+    /// Custom rules run after every declared rule of the tick, in the order
+    /// they were added, and see what those derived. A gate may fire on
+    /// absent flags; like a forbid, a flag appearing later does not undo
+    /// what it fired. Traces mark custom derivations `(custom)`.
+    pub fn rule_custom<F>(&mut self, inputs: &[&str], output: &str, gate: F)
+    where
+        F: Fn(&[bool]) -> bool + Send + Sync + 'static,
+    {
+        let inputs = inputs.iter().map(|name| self.id(name)).collect();
+        let output = self.id(output);
+        self.custom_rules.push(CustomRule {
+            inputs,
+            output,
+            gate: Arc::new(gate),
+        });
+    }
+
+    /// (output, causes) of every custom rule that fires against `active`,
+    /// in the order the rules were added.
+    pub(crate) fn custom_fired(&self, active: &FlagSet) -> Vec<(FlagId, Vec<FlagId>)> {
+        self.custom_rules
+            .iter()
+            .filter_map(|rule| Some((rule.output, rule.fire(active)?)))
+            .collect()
+    }
+
+    /// Whether some custom rule derives `id` from exactly `causes`.
+    pub(crate) fn custom_derivation(&self, id: FlagId, causes: &[FlagId]) -> bool {
+        self.custom_rules.iter().any(|rule| {
+            let present = rule.inputs.iter().filter(|id| causes.contains(id));
+            rule.output == id && present.copied().eq(causes.iter().copied())
+        })
+    }
+}
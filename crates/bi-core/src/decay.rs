@@ -0,0 +1,298 @@
+//! Temporal Decay: short-term memory that fades unless refreshed.
+//!
+//! What an expiring input leaves behind depends on the mind's
+//! `TriggerMode`: under `Strict` its conclusions go with it, under
+//! `Latched` they stay.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::hash::BuildHasher;
+
+use crate::collections::{BTreeMap, HashMap, HashState};
+use crate::engine::{Mind, Source};
+use crate::flagset::FlagSet;
+use crate::symbols::FlagId;
+
+/// How `run_to_fixpoint` ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FixpointOutcome {
+    /// A step changed nothing and no input is left to expire: memory is final.
+    Stable,
+    /// Memory came back to a state it had been in: the flags that come and
+    /// go around the cycle, ascending ids.
+    Oscillating(Vec<FlagId>),
+}
+
+/// Whether a trigger must still be active to support a conclusion, or
+/// only have been active (see `set_trigger_mode`). Only decay tells the
+/// two apart: without it inputs never fade on their own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TriggerMode {
+    /// Every trigger must currently be active: a conclusion is retracted
+    /// when an input under it expires.
+    #[default]
+    Strict,
+    /// A trigger counts once it was active this episode: conclusions drawn
+    /// while it was outlive its expiry.
+    Latched,
+}
+
+impl<T> Mind<T> {
+    /// How expiring inputs affect their conclusions; `Strict` by default.
+    ///
+    /// Under `Latched`, an expired input leaves memory but stays "latched":
+    /// truth maintenance (after this or any later retraction) still counts
+    /// it as support, so whatever it derived is kept, and `trace` shows it
+    /// missing under what it supports. A latch is only a memory of support:
+    /// new derivations need their triggers active, and an expired forbid
+    /// blocks nothing. `retract` on a latched label, re-injecting it, or
+    /// `reset_memory` releases the latch. Switching back to `Strict`
+    /// releases them all, and the conclusions resting on them are lost.
+    pub fn set_trigger_mode(&mut self, mode: TriggerMode) {
+        self.trigger_mode = mode;
+        if mode == TriggerMode::Strict && !self.latched.is_empty() {
+            self.latched.clear();
+            self.maintain();
+        }
+    }
+
+    /// The current `TriggerMode`; `Strict` unless `set_trigger_mode` changed it.
+    pub fn trigger_mode(&self) -> TriggerMode {
+        self.trigger_mode
+    }
+
+    /// Labels of the expired inputs latched as support, sorted.
+    pub fn latched(&self) -> Vec<String> {
+        let mut labels: Vec<String> = self.latched.iter().map(|id| self.label(id)).collect();
+        labels.sort();
+        labels
+    }
+
+    /// Inject facts that live for `ttl` decaying ticks (see `tick_with_decay`).
+    /// `None` makes them permanent, exactly like `inject`.
+    pub fn inject_with_ttl(&mut self, inputs: &[&str], ttl: Option<usize>) {
+        for name in inputs {
+            self.inject_one(name, 1.0);
+            match ttl {
+                Some(ticks) => {
+                    let id = self.id(name);
+                    self.lifetimes.insert(id, ticks);
+                    self.report(|r| r.input_with_ttl(name, ticks));
+                }
+                None => self.report(|r| r.input(name, None)),
+            }
+        }
+    }
+
+    /// One tick, then ages every mortal input by one; inputs whose lifetime
+    /// runs out are retracted, and truth maintenance takes their
+    /// derivations with them, unless the `TriggerMode` is `Latched`.
+    /// A flag with ttl `n` takes part in `n` ticks.
+    ///
+    /// Returns whether memory changed. Plain `tick` and `ponder` never decay.
+    pub fn tick_with_decay(&mut self) -> bool {
+        self.age += 1;
+        let changed = self.tick(self.age);
+
+        // Derived flags carry no lifetime of their own: they last as long as
+        // their support, so only inputs age.
+        let memory = &self.active_memory;
+        self.lifetimes
+            .retain(|id, _| matches!(memory.get(id), Some(Source::Input)));
+        let mut expired = Vec::new();
+        for (id, ticks) in self.lifetimes.iter_mut() {
+            *ticks = ticks.saturating_sub(1);
+            if *ticks == 0 {
+                expired.push(*id);
+            }
+        }
+        self.lifetimes.retain(|_, ticks| *ticks > 0);
+        if expired.is_empty() {
+            return changed;
+        }
+
+        expired.sort();
+        let labels: Vec<String> = expired.iter().map(|id| self.label(*id)).collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let age = self.age;
+        self.report(|r| r.decay(age));
+        match self.trigger_mode {
+            TriggerMode::Strict => {
+                self.retract(&labels);
+            }
+            TriggerMode::Latched => {
+                // Latched before maintenance runs, so they still count
+                for (id, label) in expired.iter().zip(labels) {
+                    self.active_memory.remove(id);
+                    self.note_removed(*id);
+                    self.report(|r| r.withdrawn(label));
+                }
+                self.latched.extend(expired);
+                self.maintain();
+            }
+        }
+        true
+    }
+
+    /// Decaying steps until memory is final, or until it returns to a
+    /// state seen before, which would repeat forever. One step is a
+    /// `tick_with_decay`, then the withdrawal of every conclusion a forbid
+    /// active by now contradicts (and of what rested on it). Memory is
+    /// final once a step changes nothing and no input is left to expire.
+    ///
+    /// A state is the active set with every remaining lifetime; each one is
+    /// hashed, so the guard costs one hash per step plus the states kept to
+    /// name the cycle. Only negation through a cycle (see `validate`) can
+    /// make memory come back: with stratified rules this always settles.
+    ///
+    /// `ponder` can stop at the first tick that derives nothing because
+    /// plain ticks only add flags. Decay and the truth maintenance it
+    /// triggers also remove them, and this is the loop to use then.
+    pub fn run_to_fixpoint(&mut self) -> FixpointOutcome {
+        let outcome = self.run_steps(None).unwrap_or(FixpointOutcome::Stable); // Unbounded: always ends
+        self.report(|r| r.settled());
+        outcome
+    }
+
+    /// The loop of `run_to_fixpoint`. After `max_steps` steps that all
+    /// changed memory it gives up with the flags the last one added or
+    /// removed, ascending ids (see `ponder_bounded`).
+    pub(crate) fn run_steps(
+        &mut self,
+        max_steps: Option<usize>,
+    ) -> Result<FixpointOutcome, Vec<FlagId>> {
+        let mut seen: HashMap<u64, usize> = HashMap::new();
+        let hashing = HashState::default();
+        let mut states: Vec<FlagSet> = Vec::new(); // Memory after each step
+        let mut peaks: Vec<FlagSet> = Vec::new(); // ... and right after its tick
+        let mut changed: Vec<FlagId> = Vec::new();
+        let mut steps = 0;
+        loop {
+            if max_steps.is_some_and(|max| steps == max) {
+                return Err(changed);
+            }
+            steps += 1;
+            let before = self.active_ids();
+            let ticked = self.tick_with_decay();
+            let peak = self.active_ids();
+            let withdrew = self.withdraw_inhibited();
+            if !ticked && !withdrew && self.lifetimes.is_empty() {
+                return Ok(FixpointOutcome::Stable);
+            }
+
+            let active = self.active_ids();
+            let mut touched: Vec<FlagId> = peak
+                .iter()
+                .chain(before.iter())
+                .filter(|id| !(before.contains(id) && peak.contains(id) && active.contains(id)))
+                .collect();
+            touched.sort();
+            touched.dedup();
+            changed = touched;
+
+            let lifetimes: BTreeMap<FlagId, usize> = self
+                .lifetimes
+                .iter()
+                .map(|(id, ticks)| (*id, *ticks))
+                .collect();
+            let state = hashing.hash_one((active.iter().collect::<Vec<_>>(), &lifetimes));
+
+            // A hash match is confirmed on the flags before calling it a cycle.
+            if let Some(&start) = seen.get(&state)
+                && states[start] == active
+            {
+                // Flags some step of the cycle had, and some other did not
+                let cycle: Vec<&FlagSet> = states[start..].iter().chain(&peaks[start..]).collect();
+                let mut flipping: Vec<FlagId> = cycle
+                    .iter()
+                    .flat_map(|memory| memory.iter())
+                    .filter(|id| !cycle.iter().all(|memory| memory.contains(id)))
+                    .collect();
+                flipping.sort();
+                flipping.dedup();
+                return Ok(FixpointOutcome::Oscillating(flipping));
+            }
+            seen.insert(state, states.len());
+            states.push(active);
+            peaks.push(peak);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    #[test]
+    fn fixpoint_waits_for_mortal_inputs() {
+        let mut mind = quiet();
+        mind.learn(&["A"], "B");
+        mind.inject_with_ttl(&["A"], Some(3));
+        assert_eq!(mind.run_to_fixpoint(), FixpointOutcome::Stable);
+        assert!(mind.active_labels().is_empty());
+        assert!(!mind.tick_with_decay());
+    }
+
+    #[test]
+    fn fixpoint_reports_negation_through_a_cycle() {
+        let mut mind = quiet();
+        let (a, b) = (mind.id("A"), mind.id("B"));
+        mind.rule(&["Go"], &["B"], "A");
+        mind.learn(&["A"], "B");
+        mind.inject(&["Go"]);
+        assert_eq!(
+            mind.run_to_fixpoint(),
+            FixpointOutcome::Oscillating(vec![a, b])
+        );
+    }
+
+    #[test]
+    fn strict_retracts_what_an_expired_input_supported() {
+        let mut mind = quiet();
+        assert_eq!(mind.trigger_mode(), TriggerMode::Strict);
+        mind.learn(&["A"], "B");
+        mind.inject_with_ttl(&["A"], Some(1));
+        mind.tick_with_decay();
+        assert!(!mind.is_active("A"));
+        assert!(!mind.is_active("B"));
+        assert!(mind.latched().is_empty());
+    }
+
+    #[test]
+    fn latched_keeps_what_an_expired_input_supported() {
+        let mut mind = quiet();
+        mind.set_trigger_mode(TriggerMode::Latched);
+        mind.learn(&["A"], "B");
+        mind.inject_with_ttl(&["A"], Some(1));
+        mind.tick_with_decay();
+        assert!(!mind.is_active("A"));
+        assert!(mind.is_active("B"));
+        assert_eq!(mind.latched(), ["A"]);
+
+        // Back to Strict: the latch is released and B goes with it
+        mind.set_trigger_mode(TriggerMode::Strict);
+        assert!(!mind.is_active("B"));
+    }
+
+    #[test]
+    fn a_derivation_expires_with_its_input() {
+        let mut mind = quiet();
+        mind.learn(&["Smoke"], "Alarm");
+        mind.inject_with_ttl(&["Smoke"], Some(2));
+        mind.inject(&["Power"]);
+
+        assert!(mind.tick_with_decay());
+        assert!(mind.is_active("Alarm"));
+        assert!(mind.tick_with_decay());
+        assert!(!mind.is_active("Smoke"));
+        assert!(!mind.is_active("Alarm"));
+        assert_eq!(mind.active_labels(), ["Power"]);
+    }
+}
@@ -0,0 +1,2049 @@
+//! The Thinking Engine: knowledge definition and runtime inference.
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::time::Duration;
+
+use crate::analysis::Contradiction;
+use crate::clock::Scheduled;
+use crate::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use crate::compiled::CompiledPlan;
+use crate::custom::CustomRule;
+use crate::decay::TriggerMode;
+use crate::error::MindError;
+use crate::flagset::FlagSet;
+use crate::observe::Observers;
+use crate::payload::Payloads;
+use crate::report::SharedReporter;
+use crate::rules::{Ballot, Rule, RuleChange, RuleChangeKind, Vote};
+use crate::stats::{Stats, monotonic};
+use crate::symbols::{FlagId, Normalizer, SymbolTable};
+use crate::sync::TickDiff;
+
+/// Source of a Flag's activation.
+/// Essential for "White Box" debugging and logic tracing.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    Input, // Axiom injected by user
+    // Reasoned from rules: every independent set of causes, the one that
+    // first derived the flag leading. Later supporting rules are appended.
+    Derived { justifications: Vec<Vec<FlagId>> },
+}
+
+impl Source {
+    /// The leading justification; empty for an input.
+    pub fn causes(&self) -> &[FlagId] {
+        match self {
+            Source::Input => &[],
+            Source::Derived { justifications } => justifications.first().map_or(&[], Vec::as_slice),
+        }
+    }
+}
+
+/// What a mind knows about one flag (three-valued, open world).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlagState {
+    /// Neither active nor asserted false.
+    Unknown,
+    /// Active: injected or derived.
+    True,
+    /// Asserted absent by `inject_false`.
+    False,
+}
+
+/// One firing rule in a tick: (output, causes, fuzzy value, rule weight).
+pub(crate) type Fired = (FlagId, Vec<FlagId>, f32, f32);
+
+/// What a crisp tick would commit: the indices of the firing rules, their
+/// (output, causes) and those of the custom rules, and the vote ballots.
+type Derivations = (Vec<usize>, Vec<(FlagId, Vec<FlagId>)>, Vec<Ballot>);
+
+/// The BI Runtime Kernel.
+#[derive(Clone, Debug)]
+pub struct Mind<T = ()> {
+    // --- Symbol Table (Human <-> Machine) ---
+    pub(crate) symbols: SymbolTable,
+
+    // --- Static Memory (The Brain Structure) ---
+    pub(crate) rules: Vec<Rule>,
+    pub(crate) votes: Vec<Vote>,
+    pub(crate) custom_rules: Vec<CustomRule>, // Closure gates, after the rules
+    pub(crate) exclusive_pairs: Vec<(FlagId, FlagId)>, // Must never hold together
+    pub(crate) declared_inputs: BTreeSet<FlagId>, // Flags the world may inject
+    pub(crate) declared_outputs: BTreeSet<FlagId>, // Flags the embedder reads
+    pub(crate) rule_version: u64,             // Edits of the rule base so far
+    pub(crate) changelog: Vec<RuleChange>,    // ... one entry per rule touched
+
+    // Trigger index: flag -> rules mentioning it (output included), so a
+    // tick only re-evaluates the rules whose flags changed since the last one.
+    pub(crate) trigger_index: HashMap<FlagId, Vec<usize>>,
+    pub(crate) dirty_rules: BTreeSet<usize>,
+    // Stratum of each rule; stale (emptied) whenever rules or votes change.
+    pub(crate) strata: Vec<usize>,
+    pub(crate) plan: Option<CompiledPlan>, // Dropped like `strata`, rebuilt by `compile`
+    // Rule groups: names by group id, and the ones switched off
+    pub(crate) group_names: Vec<String>,
+    pub(crate) disabled_groups: BTreeSet<usize>,
+    pub(crate) group_gates: HashMap<usize, FlagId>, // Group -> flag it waits for
+
+    // --- Dynamic Memory (Consciousness / RAM) ---
+    // Stores the active flags and the reason WHY they are active.
+    pub(crate) active_memory: HashMap<FlagId, Source>,
+    pub(crate) known_false: FlagSet, // Asserted absent; never active
+    // Flags asserted both true and false since the last reset (not the
+    // exclusive-pair `contradictions`, which are about two flags)
+    pub(crate) false_conflicts: BTreeSet<FlagId>,
+
+    // Fuzzy truth of active flags in [0, 1]; absent entries read as 1.0.
+    // Entries of inactive flags are stale and never read.
+    pub(crate) confidence: HashMap<FlagId, f32>,
+    pub(crate) activation_threshold: f32,
+    pub(crate) output_thresholds: HashMap<FlagId, f32>, // Replace it for their outputs
+    pub(crate) halt_on_contradiction: bool, // `tick` refuses to complete an exclusive pair
+    pub(crate) halted: Option<Contradiction>, // ... and what the last tick halted on
+    pub(crate) trace_weights: bool,         // `trace` shows `(w=...)` on derived flags
+    pub(crate) trace_ticks: bool,           // ... and `@tN`, the tick that derived them
+    // Tick that activated each flag, 0 for inputs; stale for inactive ones
+    pub(crate) activated_at: HashMap<FlagId, usize>,
+    pub(crate) ticks_seen: usize, // Ticks since `new` or `reset_memory`: the stamps above
+
+    // Decay: remaining ticks of mortal inputs, and ticks elapsed under decay
+    pub(crate) lifetimes: HashMap<FlagId, usize>,
+    pub(crate) age: usize,
+    pub(crate) trigger_mode: TriggerMode,
+    pub(crate) latched: FlagSet, // Expired inputs still supporting conclusions
+
+    // Derivations a forbid suppressed in the latest ticks: output -> blockers
+    pub(crate) blocked: HashMap<FlagId, Vec<FlagId>>,
+
+    // Instrumentation: rules that derived each flag this session,
+    // counting every firing rule even when another derives the same flag.
+    pub(crate) activations: HashMap<FlagId, usize>,
+
+    // Sync: changes since the last diff boundary, and the last finished diff
+    pub(crate) pending_added: BTreeSet<FlagId>,
+    pub(crate) pending_removed: BTreeSet<FlagId>,
+    pub(crate) last_diff: TickDiff,
+    pub(crate) unread_diff: TickDiff, // Changes since the last `take_diff`
+
+    // Simulation Clock (Discrete Events)
+    pub(crate) clock: usize,
+    pub(crate) schedule: BTreeMap<usize, Vec<Scheduled>>,
+
+    // Where progress events go (the console log by default)
+    pub(crate) reporter: SharedReporter,
+
+    // Instrumentation: running totals over every tick of this mind's life
+    pub(crate) stats: Stats,
+    pub(crate) timer: fn() -> Duration, // Now, for `time_elapsed` (see `set_timer`)
+
+    // Embedder callbacks for newly derived flags
+    pub(crate) observers: Observers,
+
+    // Data carried by active flags (`Mind<T>`); stale for inactive ones
+    pub(crate) payloads: Payloads<T>,
+}
+
+impl Default for Mind {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mind {
+    pub fn new() -> Self {
+        Self::with_payloads()
+    }
+
+    /// A mind resolving labels under `normalizer` (`new` compares them exactly).
+    pub fn with_normalizer(normalizer: Normalizer) -> Self {
+        Self {
+            symbols: SymbolTable::with_normalizer(normalizer),
+            ..Self::new()
+        }
+    }
+}
+
+impl<T> Mind<T> {
+    /// A mind whose flags can carry a `T` (see `inject_with`). The classic
+    /// `Mind` is `Mind<()>`, which is what `new` builds.
+    pub fn with_payloads() -> Self {
+        Self {
+            symbols: SymbolTable::new(),
+            rules: Vec::new(),
+            votes: Vec::new(),
+            custom_rules: Vec::new(),
+            exclusive_pairs: Vec::new(),
+            declared_inputs: BTreeSet::new(),
+            declared_outputs: BTreeSet::new(),
+            rule_version: 0,
+            changelog: Vec::new(),
+            trigger_index: HashMap::new(),
+            dirty_rules: BTreeSet::new(),
+            strata: Vec::new(),
+            plan: None,
+            group_names: Vec::new(),
+            disabled_groups: BTreeSet::new(),
+            group_gates: HashMap::new(),
+            active_memory: HashMap::new(),
+            known_false: FlagSet::new(),
+            false_conflicts: BTreeSet::new(),
+            confidence: HashMap::new(),
+            activation_threshold: 0.0,
+            output_thresholds: HashMap::new(),
+            halt_on_contradiction: false,
+            halted: None,
+            trace_weights: false,
+            trace_ticks: false,
+            activated_at: HashMap::new(),
+            ticks_seen: 0,
+            lifetimes: HashMap::new(),
+            age: 0,
+            trigger_mode: TriggerMode::default(),
+            latched: FlagSet::new(),
+            blocked: HashMap::new(),
+            activations: HashMap::new(),
+            pending_added: BTreeSet::new(),
+            pending_removed: BTreeSet::new(),
+            last_diff: TickDiff::default(),
+            unread_diff: TickDiff::default(),
+            clock: 0,
+            schedule: BTreeMap::new(),
+            reporter: SharedReporter::default(),
+            stats: Stats::default(),
+            timer: monotonic,
+            observers: Observers::default(),
+            payloads: Payloads::default(),
+        }
+    }
+
+    /// A derivation only activates its output when its fuzzy value exceeds
+    /// `threshold` (default 0.0, i.e. any support at all). Clamped to [0, 1].
+    /// Only `tick` is fuzzy; the static analyses treat every flag as fully true.
+    /// `set_output_threshold` overrides it for single outputs.
+    pub fn set_activation_threshold(&mut self, threshold: f32) {
+        self.activation_threshold = threshold.clamp(0.0, 1.0);
+        self.dirty_rules = (0..self.rules.len()).collect();
+    }
+
+    /// A rule deriving `label` only activates it at a fuzzy value above
+    /// `threshold`, instead of above the mind-wide threshold: e.g.
+    /// `Diagnosis` above 0.8 but `Hint` above 0.3. Clamped to [0, 1], and
+    /// exclusive like the mind-wide one, so 0.0 still takes some support.
+    /// A rule's own `min_confidence` still applies.
+    pub fn set_output_threshold(&mut self, label: &str, threshold: f32) {
+        let id = self.id(label);
+        self.output_thresholds.insert(id, threshold.clamp(0.0, 1.0));
+        self.dirty_rules = (0..self.rules.len()).collect();
+    }
+
+    /// Whether a derivation of `output` at `value` exceeds its threshold:
+    /// its own if set, else the mind-wide one.
+    pub(crate) fn clears_threshold(&self, output: FlagId, value: f32) -> bool {
+        let threshold = self
+            .output_thresholds
+            .get(&output)
+            .copied()
+            .unwrap_or(self.activation_threshold);
+        value > threshold
+    }
+
+    // ========================================================================
+    // Compile-time Helper (Knowledge Construction)
+    // ========================================================================
+
+    /// Get ID for a label, creating it if necessary.
+    pub fn id(&mut self, label: &str) -> FlagId {
+        self.symbols.id(label)
+    }
+
+    /// Get ID for `label` inside `namespace`; see `SymbolTable::id_in`.
+    pub fn id_in(&mut self, namespace: &str, label: &str) -> FlagId {
+        self.symbols.id_in(namespace, label)
+    }
+
+    /// The label of `id`, fully qualified if it lives in a namespace.
+    pub fn label(&self, id: FlagId) -> String {
+        self.symbols.label(id)
+    }
+
+    /// Make `synonym` another name of the flag `canonical`: injecting or
+    /// learning either one means the same flag, and `label` always shows
+    /// the canonical name. Aliasing an alias again re-points it.
+    ///
+    /// # Errors
+    /// If `synonym` already names a flag of its own; merging two flags is
+    /// refused, whether or not they are in use.
+    pub fn alias(&mut self, canonical: &str, synonym: &str) -> Result<(), MindError> {
+        if self.symbols.alias(canonical, synonym) {
+            Ok(())
+        } else {
+            Err(MindError::AliasConflict {
+                canonical: canonical.to_string(),
+                synonym: synonym.to_string(),
+            })
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Add a logical rule: A + B + ... -> C
+    pub fn learn(&mut self, inputs: &[&str], output: &str) {
+        self.rule(inputs, &[], output);
+    }
+
+    /// Add an inhibited rule: (A + B) - (C) -> D
+    /// Same as `rule`; `learn` is the case without forbids.
+    pub fn learn_inhibited(&mut self, triggers: &[&str], forbids: &[&str], output: &str) {
+        self.rule(triggers, forbids, output);
+    }
+
+    /// Define a rule: (A + B) - (C) -> D
+    /// Logic: IF (A and B exist) AND (C does NOT exist) THEN D exists.
+    pub fn rule(&mut self, triggers: &[&str], forbids: &[&str], output: &str) {
+        self.rule_or(triggers, &[], forbids, output);
+    }
+
+    /// Add a logical rule with an OR group: A + (B | C) -> D
+    /// An empty `any_of` makes it a plain AND rule.
+    pub fn learn_or(&mut self, triggers: &[&str], any_of: &[&str], output: &str) {
+        self.rule_or(triggers, any_of, &[], output);
+    }
+
+    /// Define a rule with an OR group: (A + (B | C)) - (E) -> D
+    /// Logic: IF A exists AND (B or C exists) AND E does NOT exist THEN D exists.
+    pub fn rule_or(&mut self, triggers: &[&str], any_of: &[&str], forbids: &[&str], output: &str) {
+        let t_ids: Vec<FlagId> = triggers.iter().map(|n| self.id(n)).collect();
+        let a_ids: Vec<FlagId> = any_of.iter().map(|n| self.id(n)).collect();
+        let f_ids: Vec<FlagId> = forbids.iter().map(|n| self.id(n)).collect();
+        let o_id = self.id(output);
+
+        self.rules.push(Rule {
+            triggers: t_ids,
+            any_of: a_ids,
+            quorum: 1,
+            forbids: f_ids,
+            output: o_id,
+            strict: false,
+            exclusive: false,
+            priority: 0,
+            min_confidence: 0.0,
+            group: None,
+            weight: 1.0,
+            added_at: 0,
+            modified_at: 0,
+        });
+        self.note_rule_edit(&[self.rules.len() - 1], RuleChangeKind::Added);
+        self.index_rule(self.rules.len() - 1);
+    }
+
+    /// Define a rule that is evaluated before lower-priority rules in the
+    /// same tick: (A + B) - (C) -> D at level `priority` (default 0).
+    ///
+    /// Rules of one level see the same memory; each level also sees what the
+    /// levels above it derived this tick. So a high-priority rule deriving a
+    /// forbid reliably suppresses a lower-priority rule matching in the same tick.
+    /// Stratification (see `strata`) already orders most such pairs; priorities
+    /// decide within a stratum, e.g. between rules that inhibit each other.
+    pub fn rule_with_priority(
+        &mut self,
+        triggers: &[&str],
+        forbids: &[&str],
+        output: &str,
+        priority: i32,
+    ) {
+        self.rule(triggers, forbids, output);
+        if let Some(rule) = self.rules.last_mut() {
+            rule.priority = priority;
+        }
+    }
+
+    /// Define a rule with a salience `weight` (default 1.0): (A + B) - (C) -> D.
+    /// Weights do not decide whether a rule fires, only the commit order
+    /// within a tick, heaviest first. Of two rules deriving the same flag
+    /// in one tick, the heavier one thus gives it its leading justification.
+    pub fn rule_with_weight(
+        &mut self,
+        triggers: &[&str],
+        forbids: &[&str],
+        output: &str,
+        weight: f32,
+    ) {
+        self.rule(triggers, forbids, output);
+        if let Some(rule) = self.rules.last_mut() {
+            rule.weight = weight;
+        }
+    }
+
+    /// Define a rule that must be sure enough: A + B -> C only when the
+    /// fuzzy AND (the weakest trigger confidence) is at least
+    /// `min_confidence`. The output then carries that value, as any fuzzy
+    /// derivation does. Clamped to [0, 1]; the activation threshold (the
+    /// mind-wide one or the output's own) still applies on top. Both bars also hold
+    /// for what `retract`, `unlearn` and decay derive silently.
+    pub fn rule_fuzzy(&mut self, triggers: &[&str], output: &str, min_confidence: f32) {
+        self.rule(triggers, &[], output);
+        if let Some(rule) = self.rules.last_mut() {
+            rule.min_confidence = min_confidence.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Add a threshold rule: at least `k` of the inputs -> C
+    ///
+    /// # Errors
+    /// If `k` is 0 or exceeds the number of inputs; nothing is learned.
+    pub fn rule_threshold(
+        &mut self,
+        inputs: &[&str],
+        k: usize,
+        output: &str,
+    ) -> Result<(), MindError> {
+        self.learn_threshold(&[], inputs, k, output)
+    }
+
+    /// Add a threshold rule with mandatory flags: A + (at least `k` of B, C, ...) -> D
+    ///
+    /// # Errors
+    /// If `k` is 0 or exceeds the number of optional inputs; nothing is learned.
+    pub fn learn_threshold(
+        &mut self,
+        triggers: &[&str],
+        inputs: &[&str],
+        k: usize,
+        output: &str,
+    ) -> Result<(), MindError> {
+        if !(1..=inputs.len()).contains(&k) {
+            return Err(MindError::InvalidThreshold {
+                output: output.to_string(),
+                k,
+                inputs: inputs.len(),
+            });
+        }
+        self.rule_or(triggers, inputs, &[], output);
+        if let Some(rule) = self.rules.last_mut() {
+            rule.quorum = k;
+        }
+        Ok(())
+    }
+
+    /// Add an exclusive rule: (A ^ B ^ ...) -> C
+    /// Logic: IF exactly one of the inputs exists THEN C exists.
+    ///
+    /// A second input arriving later only stops further derivations: an output
+    /// derived while one input was alone stays active until a `retract` re-checks memory.
+    pub fn rule_xor(&mut self, inputs: &[&str], output: &str) {
+        self.rule_or(&[], inputs, &[], output);
+        if let Some(rule) = self.rules.last_mut() {
+            rule.exclusive = true;
+        }
+    }
+
+    /// Define a strict rule: (A + B) - (C known false) -> D
+    /// Logic: IF (A and B exist) AND (C was asserted false) THEN D exists.
+    /// An unknown forbid blocks a strict rule (see `fires`).
+    pub fn rule_strict(&mut self, triggers: &[&str], forbids: &[&str], output: &str) {
+        self.rule(triggers, forbids, output);
+        if let Some(rule) = self.rules.last_mut() {
+            rule.strict = true;
+        }
+    }
+
+    /// Declare a competition: once any candidate scores above zero, the
+    /// highest-scoring output activates and the others stay off.
+    /// Ties go to the candidate declared first.
+    pub fn learn_vote(&mut self, candidates: &[(&str, &[(&str, f32)])]) {
+        let candidates = candidates
+            .iter()
+            .map(|(output, triggers)| {
+                let weighted = triggers.iter().map(|(t, w)| (self.id(t), *w)).collect();
+                (self.id(output), weighted)
+            })
+            .collect();
+        self.votes.push(Vote { candidates });
+        self.strata.clear();
+    }
+
+    /// Remove the first rule `learn` could have made: exactly these triggers
+    /// (in any order), this output, no group members, forbids or rule group,
+    /// and default priority, weight and confidence bar. Returns whether one
+    /// was found; every other rule is left alone.
+    ///
+    /// Conclusions that only that rule justified are withdrawn through truth
+    /// maintenance (see `retract`); other rules for the same output keep theirs.
+    pub fn unlearn(&mut self, triggers: &[&str], output: &str) -> bool {
+        let Some(o_id) = self.symbols.get(output) else {
+            return false;
+        };
+        let Some(mut t_ids) = triggers
+            .iter()
+            .map(|n| self.symbols.get(n))
+            .collect::<Option<Vec<FlagId>>>()
+        else {
+            return false;
+        };
+        t_ids.sort();
+
+        let found = self.rules.iter().position(|rule| {
+            let mut own = rule.triggers.clone();
+            own.sort();
+            rule.output == o_id && own == t_ids && rule.is_plain()
+        });
+        let Some(index) = found else {
+            return false;
+        };
+
+        self.note_rule_edit(&[index], RuleChangeKind::Removed);
+        self.rules.remove(index);
+        self.reindex(); // Indices after `index` shifted
+        self.maintain();
+        true
+    }
+
+    /// Every edit of the rule base, oldest first: rules added, removed,
+    /// or switched off and on with their group. The rule base of version
+    /// `v` is what the entries up to `v` built; each rule's `added_at` and
+    /// `modified_at` point into this log.
+    pub fn rule_changelog(&self) -> Vec<RuleChange> {
+        self.changelog.clone()
+    }
+
+    /// The rule version: how many edits the rule base has seen.
+    pub fn rule_version(&self) -> u64 {
+        self.rule_version
+    }
+
+    /// One more rule version, touching the rules at `indices`: stamps them
+    /// and logs `kind` for each.
+    pub(crate) fn note_rule_edit(&mut self, indices: &[usize], kind: RuleChangeKind) {
+        self.rule_version += 1;
+        for &index in indices {
+            let rule = &mut self.rules[index];
+            if kind == RuleChangeKind::Added {
+                rule.added_at = self.rule_version;
+            }
+            rule.modified_at = self.rule_version;
+            let output = self.symbols.label(rule.output);
+            self.changelog.push(RuleChange {
+                version: self.rule_version,
+                rule: index,
+                output,
+                kind,
+            });
+        }
+    }
+
+    // --- Trigger Index ---
+
+    /// Register the rule at `index` under every flag it mentions.
+    fn index_rule(&mut self, index: usize) {
+        self.strata.clear();
+        self.plan = None;
+        let rule = &self.rules[index];
+        let mut mentioned: Vec<FlagId> = rule
+            .triggers
+            .iter()
+            .chain(&rule.any_of)
+            .chain(&rule.forbids)
+            .copied()
+            .collect();
+        mentioned.push(rule.output);
+        mentioned.extend(self.gate(rule));
+        mentioned.sort();
+        mentioned.dedup();
+        for id in mentioned {
+            self.trigger_index.entry(id).or_default().push(index);
+        }
+        self.dirty_rules.insert(index);
+    }
+
+    /// Rebuild the index after `rules` was replaced or edited in place.
+    pub(crate) fn reindex(&mut self) {
+        self.trigger_index.clear();
+        self.plan = None;
+        self.dirty_rules.clear();
+        for index in 0..self.rules.len() {
+            self.index_rule(index);
+        }
+    }
+
+    /// Queue every rule mentioning `id` for the next tick.
+    pub(crate) fn touch(&mut self, id: FlagId) {
+        if let Some(rules) = self.trigger_index.get(&id) {
+            self.dirty_rules.extend(rules);
+        }
+    }
+
+    // ========================================================================
+    // Runtime Execution (Inference)
+    // ========================================================================
+
+    /// Ids of every active flag.
+    pub(crate) fn active_ids(&self) -> FlagSet {
+        self.active_memory.keys().copied().collect()
+    }
+
+    /// Ids of the injected subset of the active flags.
+    pub(crate) fn input_ids(&self) -> FlagSet {
+        self.active_memory
+            .iter()
+            .filter(|(_, source)| matches!(source, Source::Input))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Forget everything dynamic: active flags, assertions and counters.
+    pub fn reset_memory(&mut self) {
+        self.active_memory.clear();
+        self.known_false.clear();
+        self.false_conflicts.clear();
+        self.confidence.clear();
+        self.activated_at.clear();
+        self.ticks_seen = 0;
+        self.lifetimes.clear();
+        self.latched.clear();
+        self.age = 0;
+        self.blocked.clear();
+        self.activations.clear();
+        self.halted = None;
+        self.dirty_rules = (0..self.rules.len()).collect();
+        // Mirrors must resync from a full copy after a reset.
+        self.pending_added.clear();
+        self.pending_removed.clear();
+        self.last_diff = TickDiff::default();
+        self.unread_diff = TickDiff::default();
+    }
+
+    /// Forget every derived fact but keep the injected axioms,
+    /// so the next run re-derives from the same inputs (e.g. after editing rules).
+    pub fn reset_derivations(&mut self) {
+        let derived: Vec<FlagId> = self
+            .active_memory
+            .iter()
+            .filter(|(_, source)| matches!(source, Source::Derived { .. }))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in derived {
+            self.active_memory.remove(&id);
+            self.note_removed(id);
+        }
+        self.blocked.clear();
+    }
+
+    /// Inject initial facts (Stimuli).
+    pub fn inject(&mut self, inputs: &[&str]) {
+        for name in inputs {
+            self.inject_one(name, 1.0);
+            self.report(|r| r.input(name, None));
+        }
+    }
+
+    /// `inject`, logged as one `[Input] + \`A\`, \`B\`` line (a single
+    /// reporter call) instead of one line per label. Nothing is logged for
+    /// an empty batch; a batch of one logs exactly like `inject`.
+    pub fn inject_batch(&mut self, inputs: &[&str]) {
+        for name in inputs {
+            self.inject_one(name, 1.0);
+        }
+        match inputs {
+            [] => {}
+            [name] => self.report(|r| r.input(name, None)),
+            _ => self.report(|r| r.inputs(inputs)),
+        }
+    }
+
+    /// Inject facts with a confidence in [0, 1] each (noisy perception).
+    /// `inject` is the case where every confidence is 1.0.
+    pub fn inject_weighted(&mut self, inputs: &[(&str, f32)]) {
+        for (name, confidence) in inputs {
+            let confidence = confidence.clamp(0.0, 1.0);
+            self.inject_one(name, confidence);
+            self.report(|r| r.input(name, Some(confidence)));
+        }
+    }
+
+    pub(crate) fn inject_one(&mut self, name: &str, confidence: f32) {
+        let id = self.id(name);
+        if self.known_false.remove(&id) {
+            self.false_conflicts.insert(id); // The newer assertion wins
+        }
+        self.lifetimes.remove(&id); // Re-injection makes it permanent again
+        self.latched.remove(&id);
+        self.clear_payload(id);
+        self.activated_at.insert(id, 0);
+        if self.active_memory.insert(id, Source::Input).is_none() {
+            self.note_added(id);
+        } else if self.confidence(name) != Some(confidence) {
+            self.touch(id); // Same flag, new strength
+        }
+        self.confidence.insert(id, confidence);
+    }
+
+    /// Assert flags as definitely absent (open world), instead of merely
+    /// not injected. A flag that is already active stays true: the
+    /// assertion is refused and reported.
+    pub fn inject_false(&mut self, inputs: &[&str]) {
+        for name in inputs {
+            let id = self.id(name);
+            if self.active_memory.contains_key(&id) {
+                self.false_conflicts.insert(id);
+                self.report(|r| r.asserted_false(name, true));
+                continue;
+            }
+            self.known_false.insert(id);
+            self.touch(id);
+            self.report(|r| r.asserted_false(name, false));
+        }
+    }
+
+    /// Whether `rule` would fire against `active` this tick.
+    ///
+    /// Each flag is True (active), False (asserted by `inject_false`) or
+    /// Unknown (neither). Triggers need True. Forbids read:
+    ///
+    /// | forbid  | normal rule | strict rule |
+    /// |---------|-------------|-------------|
+    /// | True    | blocks      | blocks      |
+    /// | False   | passes      | passes      |
+    /// | Unknown | passes      | blocks      |
+    pub fn fires(&self, rule: &Rule, active: &FlagSet) -> bool {
+        // Optimization: Don't re-derive known facts
+        !active.contains(&rule.output) && self.satisfied(rule, active)
+    }
+
+    /// Whether the conditions of `rule` hold against `active`,
+    /// regardless of its output already being there.
+    pub(crate) fn satisfied(&self, rule: &Rule, active: &FlagSet) -> bool {
+        self.triggered(rule, active) && self.blockers(rule, active).is_empty()
+    }
+
+    /// Whether the positive conditions of `rule` hold, ignoring its forbids.
+    pub(crate) fn triggered(&self, rule: &Rule, active: &FlagSet) -> bool {
+        // Logic Gate: AND (All triggers must be present)
+        let triggers_met = rule.triggers.iter().all(|t| active.contains(t));
+        if !triggers_met {
+            return false;
+        }
+
+        // Logic Gate: OR / K-of-N / XOR (A non-empty group needs `quorum` members
+        // present, and an exclusive group no more than one)
+        if !rule.any_of.is_empty() {
+            let present = rule.any_of.iter().filter(|a| active.contains(a)).count();
+            if present < rule.quorum || (rule.exclusive && present > 1) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether no forbid of a strict `rule` is unknown (see `fires`).
+    fn strict_passes(&self, rule: &Rule, active: &FlagSet) -> bool {
+        !rule.strict
+            || rule
+                .forbids
+                .iter()
+                .all(|f| active.contains(f) || self.known_false.contains(f))
+    }
+
+    /// Forbids of `rule` currently inhibiting it (Inhibition / NOT Gate).
+    fn blockers(&self, rule: &Rule, active: &FlagSet) -> Vec<FlagId> {
+        rule.forbids
+            .iter()
+            .copied()
+            .filter(|f| active.contains(f) || (rule.strict && !self.known_false.contains(f)))
+            .collect()
+    }
+
+    /// Remember which of the `candidates` are suppressed only by forbids.
+    fn note_blocked(&mut self, active: &FlagSet, candidates: &[usize]) {
+        let blocked: Vec<(FlagId, Vec<FlagId>)> = candidates
+            .iter()
+            .map(|&index| &self.rules[index])
+            .filter(|rule| !active.contains(&rule.output) && self.triggered(rule, active))
+            .map(|rule| (rule.output, self.blockers(rule, active)))
+            .filter(|(_, blockers)| !blockers.is_empty())
+            .collect();
+        self.blocked.extend(blocked);
+    }
+
+    /// What a satisfied `rule` was reasoned from: its triggers, then the
+    /// strongest present members of its group that meet the quorum
+    /// (in declaration order among equals).
+    pub(crate) fn causes(&self, rule: &Rule, active: &FlagSet) -> Vec<FlagId> {
+        let mut members: Vec<FlagId> = rule
+            .any_of
+            .iter()
+            .copied()
+            .filter(|a| active.contains(a))
+            .collect();
+        members.sort_by(|a, b| self.strength_of(*b).total_cmp(&self.strength_of(*a)));
+
+        let mut causes = rule.triggers.clone();
+        causes.extend(self.gate(rule).filter(|gate| !causes.contains(gate)));
+        causes.extend(members.into_iter().take(rule.quorum));
+        causes
+    }
+
+    /// Fuzzy truth of an active flag; 1.0 unless injected or derived weaker.
+    pub(crate) fn strength_of(&self, id: FlagId) -> f32 {
+        self.confidence.get(&id).copied().unwrap_or(1.0)
+    }
+
+    /// Fuzzy value of a derivation over `causes`: the weakest cause (fuzzy AND),
+    /// scaled down by the strongest active forbid (inhibition).
+    pub(crate) fn fuzzy_value(&self, rule: &Rule, causes: &[FlagId], active: &FlagSet) -> f32 {
+        let support = causes
+            .iter()
+            .map(|c| self.strength_of(*c))
+            .fold(1.0, f32::min);
+        let inhibition = rule
+            .forbids
+            .iter()
+            .filter(|f| active.contains(f))
+            .map(|f| self.strength_of(*f))
+            .fold(0.0, f32::max);
+        support * (1.0 - inhibition)
+    }
+
+    /// Confidence of an active flag, `None` if it is unknown or inactive.
+    /// Boolean use (plain `inject`) gives 1.0 everywhere.
+    pub fn confidence(&self, label: &str) -> Option<f32> {
+        let id = self.symbols.get(label)?;
+        self.active_memory
+            .contains_key(&id)
+            .then(|| self.strength_of(id))
+    }
+
+    /// `indices` grouped by rule priority, highest level first,
+    /// in declaration order within a level.
+    pub(crate) fn priority_levels(&self, mut indices: Vec<usize>) -> Vec<Vec<usize>> {
+        indices.sort_by_key(|&index| (Reverse(self.rules[index].priority), index));
+        indices
+            .chunk_by(|a, b| self.rules[*a].priority == self.rules[*b].priority)
+            .map(<[usize]>::to_vec)
+            .collect()
+    }
+
+    /// Rules that would fire against `active` this tick. Pure scan, no side effects.
+    /// All levels are read against `active`; see `derivations` for priorities.
+    pub(crate) fn firing_rules<'a>(
+        &'a self,
+        active: &'a FlagSet,
+    ) -> impl Iterator<Item = &'a Rule> {
+        self.rules
+            .iter()
+            .filter(move |rule| self.fires(rule, active))
+    }
+
+    /// Votes still undecided against `active` that now have a winner.
+    pub(crate) fn ballots(&self, active: &FlagSet) -> Vec<Ballot> {
+        let mut ballots = Vec::new();
+        for vote in &self.votes {
+            if vote.candidates.iter().any(|(out, _)| active.contains(out)) {
+                continue; // Already decided
+            }
+
+            let scores: Vec<(FlagId, f32)> = vote
+                .candidates
+                .iter()
+                .map(|(out, triggers)| {
+                    let score = triggers
+                        .iter()
+                        .filter(|(t, _)| active.contains(t))
+                        .map(|(_, w)| w)
+                        .sum();
+                    (*out, score)
+                })
+                .collect();
+
+            let mut best: Option<usize> = None;
+            for (i, (_, score)) in scores.iter().enumerate() {
+                if *score > 0.0 && best.is_none_or(|b| *score > scores[b].1) {
+                    best = Some(i);
+                }
+            }
+
+            if let Some(best) = best {
+                let causes = vote.candidates[best]
+                    .1
+                    .iter()
+                    .map(|(t, _)| *t)
+                    .filter(|t| active.contains(t))
+                    .collect();
+                ballots.push(Ballot {
+                    winner: scores[best].0,
+                    causes,
+                    scores,
+                });
+            }
+        }
+        ballots
+    }
+
+    /// The cached rule strata, or freshly computed ones if rules changed
+    /// since the last tick.
+    pub(crate) fn current_strata(&self) -> Cow<'_, [usize]> {
+        if self.strata.len() == self.rules.len() {
+            Cow::Borrowed(&self.strata)
+        } else {
+            Cow::Owned(self.rule_strata())
+        }
+    }
+
+    /// `indices` grouped by stratum, lowest first (see `strata`).
+    pub(crate) fn by_stratum(&self, indices: Vec<usize>, strata: &[usize]) -> Vec<Vec<usize>> {
+        let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for index in indices {
+            groups.entry(strata[index]).or_default().push(index);
+        }
+        groups.into_values().collect()
+    }
+
+    /// What one tick would commit against `active`: (output, causes) for
+    /// every firing rule of the lowest stratum that fires and every firing
+    /// custom rule, then the ballots of newly decided votes. Skips the rule at `disabled`.
+    /// Also returns the indices of the rules that fired, in order.
+    fn derivations_with(
+        &self,
+        active: &FlagSet,
+        disabled: Option<usize>,
+        strata: &[usize],
+    ) -> Derivations {
+        let enabled = (0..self.rules.len())
+            .filter(|index| Some(*index) != disabled && self.rule_enabled(*index, active));
+        let mut firing = Vec::new();
+        let mut fired = Vec::new();
+        let mut reached = Cow::Borrowed(active);
+        for group in self.by_stratum(enabled.collect(), strata) {
+            for level in self.priority_levels(group) {
+                let level_firing: Vec<usize> = level
+                    .into_iter()
+                    .filter(|&index| self.fires(&self.rules[index], &reached))
+                    .collect();
+                let level_fired: Vec<(FlagId, Vec<FlagId>)> = level_firing
+                    .iter()
+                    .map(|&index| &self.rules[index])
+                    .map(|rule| (rule.output, self.causes(rule, &reached)))
+                    .collect();
+                if !level_fired.is_empty() {
+                    reached
+                        .to_mut()
+                        .extend(level_fired.iter().map(|(out, _)| *out));
+                }
+                firing.extend(level_firing);
+                fired.extend(level_fired);
+            }
+            if !fired.is_empty() {
+                break; // Higher strata wait until this one is settled
+            }
+        }
+        let custom = self.custom_fired(&reached);
+        fired.extend(custom.into_iter().filter(|(out, _)| !reached.contains(out)));
+        (firing, fired, self.ballots(active))
+    }
+
+    /// The derivations (not the ballots) one tick would commit against
+    /// `active`, fuzzy like `tick`: each rule through `evaluate`, so the
+    /// activation threshold and `min_confidence` apply, then the custom rules.
+    fn fuzzy_derivations(&self, active: &FlagSet) -> Vec<Fired> {
+        let strata = self.current_strata();
+        let enabled = (0..self.rules.len())
+            .filter(|index| self.rule_enabled(*index, active))
+            .collect();
+        let mut fired: Vec<Fired> = Vec::new();
+        let mut reached = Cow::Borrowed(active);
+        for group in self.by_stratum(enabled, &strata) {
+            for level in self.priority_levels(group) {
+                let level_fired: Vec<Fired> = self
+                    .evaluate(&level, &reached)
+                    .into_iter()
+                    .filter(|(output, ..)| !reached.contains(output))
+                    .collect();
+                if !level_fired.is_empty() {
+                    reached
+                        .to_mut()
+                        .extend(level_fired.iter().map(|(out, ..)| *out));
+                }
+                fired.extend(level_fired);
+            }
+            if !fired.is_empty() {
+                break; // Higher strata wait until this one is settled
+            }
+        }
+        for (output_id, causes) in self.custom_fired(&reached) {
+            if !reached.contains(&output_id) {
+                let value = causes
+                    .iter()
+                    .map(|c| self.strength_of(*c))
+                    .fold(1.0, f32::min);
+                fired.push((output_id, causes, value, 1.0));
+            }
+        }
+        fired
+    }
+
+    /// Outputs one silent tick adds to `active`, skipping the rule at `disabled`.
+    pub(crate) fn step(&self, active: &FlagSet, disabled: Option<usize>) -> Vec<FlagId> {
+        self.step_with(active, disabled, &self.current_strata())
+    }
+
+    fn step_with(
+        &self,
+        active: &FlagSet,
+        disabled: Option<usize>,
+        strata: &[usize],
+    ) -> Vec<FlagId> {
+        self.step_firing(active, disabled, strata).1
+    }
+
+    /// `step_with`, also naming the rules that fired (by index).
+    pub(crate) fn step_firing(
+        &self,
+        active: &FlagSet,
+        disabled: Option<usize>,
+        strata: &[usize],
+    ) -> (Vec<usize>, Vec<FlagId>) {
+        let (firing, fired, ballots) = self.derivations_with(active, disabled, strata);
+        let mut outputs: Vec<FlagId> = fired.into_iter().map(|(out, _)| out).collect();
+        outputs.extend(ballots.into_iter().map(|b| b.winner));
+        (firing, outputs)
+    }
+
+    /// Silently runs the same tick loop as `ponder` on a bare set of active flags.
+    pub fn settle(&self, active: FlagSet) -> FlagSet {
+        self.settle_without(active, None)
+    }
+
+    /// `settle`, pretending the rule at index `disabled` does not exist.
+    pub fn settle_without(&self, mut active: FlagSet, disabled: Option<usize>) -> FlagSet {
+        let strata = self.current_strata();
+        loop {
+            let outputs = self.step_with(&active, disabled, &strata);
+            if outputs.is_empty() {
+                return active;
+            }
+            active.extend(outputs);
+        }
+    }
+
+    /// Run one cycle of thought processing.
+    /// Returns true if the mind state changed (new thoughts derived).
+    ///
+    /// The order is reproducible: derivations are committed, reported and
+    /// observed heaviest rule weight first, then by stratum, priority level
+    /// and rule declaration order, never in hash order. Not sorting by id is deliberate: ids depend on
+    /// the order labels were first seen, rule order only on the rule base.
+    pub fn tick(&mut self, tick_count: usize) -> bool {
+        self.tick_using(tick_count, Self::evaluate)
+    }
+
+    /// The rules of one priority `level` that fire against `reached`, in order:
+    /// (output, causes, fuzzy value). Pure, so levels can be split across threads.
+    ///
+    /// A rule fires when its fuzzy value clears the threshold and its own
+    /// `min_confidence`; at full confidence that is exactly `fires` (an
+    /// active forbid zeroes it).
+    pub(crate) fn evaluate(&self, level: &[usize], reached: &FlagSet) -> Vec<Fired> {
+        level
+            .iter()
+            .map(|&index| &self.rules[index])
+            .filter(|rule| self.triggered(rule, reached))
+            .filter(|rule| self.strict_passes(rule, reached))
+            .filter_map(|rule| {
+                let causes = self.causes(rule, reached);
+                let value = self.fuzzy_value(rule, &causes, reached);
+                let clears =
+                    self.clears_threshold(rule.output, value) && value >= rule.min_confidence;
+                clears.then_some((rule.output, causes, value, rule.weight))
+            })
+            .collect()
+    }
+
+    /// `tick`, with the rules of each level evaluated by `evaluate`.
+    pub(crate) fn tick_using(
+        &mut self,
+        tick_count: usize,
+        evaluate: fn(&Self, &[usize], &FlagSet) -> Vec<Fired>,
+    ) -> bool {
+        let started = (self.timer)();
+        self.stats.ticks_run += 1;
+        self.ticks_seen += 1;
+        self.halted = None;
+        self.report(|r| r.tick_started(tick_count));
+        if self.strata.len() != self.rules.len() {
+            self.strata = self.rule_strata();
+        }
+
+        // 1. Scan the rules whose flags changed against Active Memory,
+        // lowest stratum first, level by level in priority order, in
+        // declaration order within a level like a full scan would.
+        // Each level sees what the ones above derived; a higher stratum
+        // waits for a tick where the lower ones derive nothing.
+        let active = self.active_ids();
+        // Disabled rules drop out; enabling their group or activating its
+        // gate queues them again.
+        let candidates: Vec<usize> = core::mem::take(&mut self.dirty_rules)
+            .into_iter()
+            .filter(|&index| self.rule_enabled(index, &active))
+            .collect();
+        let scanned = if self.halt_on_contradiction {
+            candidates.clone()
+        } else {
+            Vec::new()
+        };
+        let ballots = self.ballots(&active);
+        let mut reached = active;
+        let mut new_facts: Vec<Fired> = Vec::new();
+        let mut alternatives: Vec<Fired> = Vec::new();
+        for group in self.by_stratum(candidates, &self.strata) {
+            if !new_facts.is_empty() {
+                self.dirty_rules.extend(group); // Deferred to a later tick
+                continue;
+            }
+            for level in self.priority_levels(group) {
+                self.stats.rules_evaluated += level.len();
+                // One whose output already holds adds another justification.
+                let (known, level_facts): (Vec<_>, Vec<_>) = evaluate(self, &level, &reached)
+                    .into_iter()
+                    .partition(|(output, ..)| reached.contains(output));
+                self.note_blocked(&reached, &level);
+                reached.extend(level_facts.iter().map(|(out, ..)| *out));
+                new_facts.extend(level_facts);
+                alternatives.extend(known);
+            }
+        }
+        // Custom rules: one last level, seeing everything the rules reached.
+        for (output_id, causes) in self.custom_fired(&reached) {
+            let value = causes
+                .iter()
+                .map(|c| self.strength_of(*c))
+                .fold(1.0, f32::min);
+            if reached.insert(output_id) {
+                new_facts.push((output_id, causes, value, 1.0));
+            } else {
+                alternatives.push((output_id, causes, value, 1.0));
+            }
+        }
+
+        // A halting mind commits nothing that completes an exclusive pair.
+        if self.halt_on_contradiction {
+            let winners: Vec<FlagId> = ballots.iter().map(|ballot| ballot.winner).collect();
+            if let Some(found) = self.contradiction_in(tick_count, &new_facts, &winners) {
+                self.dirty_rules.extend(scanned); // Evaluated again next tick
+                self.halted = Some(found);
+                self.flush_diff(self.ticks_seen);
+                self.stats.time_elapsed += (self.timer)().saturating_sub(started);
+                return false;
+            }
+        }
+
+        // Re-derivations are recorded silently: memory itself does not change.
+        for (output_id, causes, value, _) in alternatives {
+            if matches!(
+                self.active_memory.get(&output_id),
+                Some(Source::Derived { .. })
+            ) {
+                self.commit(self.ticks_seen, output_id, causes, value);
+            }
+        }
+
+        if new_facts.is_empty() && ballots.is_empty() {
+            self.flush_diff(self.ticks_seen); // Injections alone still count as a change
+            self.stats.time_elapsed += (self.timer)().saturating_sub(started);
+            return false;
+        }
+
+        // 2. Commit new facts to memory (Neuron Activation), heaviest first;
+        // the sort is stable, so equal weights keep the scan order.
+        new_facts.sort_by(|a, b| b.3.total_cmp(&a.3));
+        let mut logged = 0;
+        let mut committed = Vec::new();
+        for (output_id, causes, value, _) in new_facts {
+            self.activate(tick_count, output_id, causes, value);
+            committed.push(output_id);
+            logged += 1;
+        }
+
+        // Commit Votes (scores shown for transparency)
+        for ballot in ballots {
+            let scores: Vec<(String, f32)> = ballot
+                .scores
+                .iter()
+                .map(|(out, score)| (self.label(*out), *score))
+                .collect();
+            self.report(|r| r.vote(tick_count, &scores));
+            let value = ballot
+                .causes
+                .iter()
+                .map(|c| self.strength_of(*c))
+                .fold(1.0, f32::min);
+            committed.push(ballot.winner);
+            self.activate(tick_count, ballot.winner, ballot.causes, value);
+            logged += 1;
+        }
+
+        self.flush_diff(self.ticks_seen);
+        let mut reported = HashSet::new();
+        committed.retain(|id| reported.insert(*id)); // Several rules, one report
+        self.notify(&committed);
+        self.stats.facts_derived += logged;
+        self.stats.time_elapsed += (self.timer)().saturating_sub(started);
+        true
+    }
+
+    /// Reports and stores one derivation.
+    fn activate(&mut self, tick_count: usize, output_id: FlagId, causes: Vec<FlagId>, value: f32) {
+        let cause_labels: Vec<String> = causes.iter().map(|id| self.label(*id)).collect();
+        let output = self.label(output_id);
+        self.report(|r| r.derived(tick_count, &cause_labels, &output, value));
+
+        self.commit(self.ticks_seen, output_id, causes, value);
+    }
+
+    /// Stores a derivation without logging it. A flag derived again gains
+    /// the justification, and the strongest value (fuzzy OR); each new
+    /// justification counts towards `activation_multiplicity`.
+    /// A new flag is stamped with `stamp` (0: outside any tick).
+    pub(crate) fn commit(
+        &mut self,
+        stamp: usize,
+        output_id: FlagId,
+        causes: Vec<FlagId>,
+        value: f32,
+    ) {
+        match self.active_memory.get_mut(&output_id) {
+            Some(Source::Derived { justifications }) => {
+                if !justifications.contains(&causes) {
+                    justifications.push(causes);
+                    *self.activations.entry(output_id).or_default() += 1;
+                }
+                let strongest = self.strength_of(output_id).max(value);
+                self.confidence.insert(output_id, strongest);
+            }
+            Some(Source::Input) => {} // Axioms need no justification
+            None => {
+                self.derive_payload(output_id, &causes);
+                let justifications = vec![causes];
+                self.active_memory
+                    .insert(output_id, Source::Derived { justifications });
+                self.confidence.insert(output_id, value);
+                self.activated_at.insert(output_id, stamp);
+                *self.activations.entry(output_id).or_default() += 1;
+                self.note_added(output_id);
+            }
+        }
+        self.blocked.remove(&output_id);
+        if self.known_false.remove(&output_id) {
+            // A derivation overrides a false assertion, but remembers it did
+            self.false_conflicts.insert(output_id);
+        }
+    }
+
+    /// Run thought cycles until the mind state stabilizes.
+    pub fn ponder(&mut self) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("ponder").entered();
+        let mut tick = 1;
+        while self.tick(tick) {
+            tick += 1;
+        }
+        self.report(|r| r.settled());
+    }
+
+    /// Withdraws inputs, then removes every conclusion that no longer rests
+    /// on the remaining ones (justification-based truth maintenance).
+    ///
+    /// A conclusion survives while any of its justifications, or another rule,
+    /// still supports it from well-founded flags; its source keeps only the
+    /// justifications that still hold. Mutually supporting conclusions without
+    /// such a root fall together. Conclusions the withdrawal unblocks are then
+    /// derived silently, under the activation threshold and each rule's
+    /// `min_confidence` as in `tick`.
+    ///
+    /// Flags latched by `TriggerMode::Latched` are released too.
+    ///
+    /// Returns the labels actually removed, inputs included, sorted.
+    pub fn retract(&mut self, inputs: &[&str]) -> Vec<String> {
+        let mut withdrawn = HashSet::new();
+        for name in inputs {
+            if let Some(id) = self.symbols.get(name) {
+                self.latched.remove(&id);
+                if matches!(self.active_memory.get(&id), Some(Source::Input)) {
+                    self.active_memory.remove(&id);
+                    self.note_removed(id);
+                    withdrawn.insert(id);
+                }
+                self.report(|r| r.withdrawn(name));
+            }
+        }
+
+        let mut removed: Vec<String> = withdrawn.iter().map(|id| self.label(*id)).collect();
+        removed.extend(self.maintain());
+        removed.sort();
+        removed
+    }
+
+    /// Switches the input `label` on or off and reconciles everything
+    /// downstream, leaving the memory a fresh run on the new input set
+    /// would reach. The input is injected or withdrawn, conclusions that
+    /// lose their support or are now forbidden are lost (truth maintenance,
+    /// as in `retract`), and what became derivable is pondered, logged like
+    /// any derivation. Switching off a flag that is not an input only
+    /// reconciles.
+    pub fn set_input(&mut self, label: &str, active: bool) {
+        if active {
+            self.inject_one(label, 1.0);
+            self.report(|r| r.input(label, None));
+        } else if let Some(id) = self.symbols.get(label)
+            && matches!(self.active_memory.get(&id), Some(Source::Input))
+        {
+            self.active_memory.remove(&id);
+            self.note_removed(id);
+            self.report(|r| r.withdrawn(label));
+        }
+        self.reconcile(false);
+        self.ponder();
+    }
+
+    /// Re-checks every conclusion against the current inputs and rules,
+    /// as described for `retract`. Logs and returns the lost labels, sorted.
+    pub(crate) fn maintain(&mut self) -> Vec<String> {
+        self.reconcile(true)
+    }
+
+    /// `maintain`; without `settle`, unblocked conclusions are left to the
+    /// next `ponder` (which logs them) instead of being derived silently.
+    fn reconcile(&mut self, settle: bool) -> Vec<String> {
+        // Sweep: grow the well-founded set from the remaining inputs.
+        let mut supported = self.input_ids().union(&self.latched);
+        let mut pending: Vec<FlagId> = self
+            .active_memory
+            .iter()
+            .filter(|(_, source)| matches!(source, Source::Derived { .. }))
+            .map(|(id, _)| *id)
+            .collect();
+        pending.sort();
+        loop {
+            let count = pending.len();
+            let mut unresolved = Vec::new();
+            for id in core::mem::take(&mut pending) {
+                match self.justifications(id, &supported) {
+                    Some(justifications) => {
+                        self.rejustify(id, justifications);
+                        supported.insert(id);
+                    }
+                    None => unresolved.push(id),
+                }
+            }
+            pending = unresolved;
+            if pending.len() == count {
+                break;
+            }
+        }
+
+        // Alternatives resting on flags resolved later in the sweep.
+        let mut resolved: Vec<FlagId> = supported
+            .iter()
+            .filter(|id| matches!(self.active_memory.get(id), Some(Source::Derived { .. })))
+            .collect();
+        resolved.sort();
+        for id in resolved {
+            for causes in self.justifications(id, &supported).unwrap_or_default() {
+                if let Some(Source::Derived { justifications }) = self.active_memory.get_mut(&id)
+                    && !justifications.contains(&causes)
+                {
+                    justifications.push(causes);
+                    *self.activations.entry(id).or_default() += 1;
+                }
+            }
+        }
+
+        let mut lost = Vec::new();
+        for id in pending {
+            self.active_memory.remove(&id);
+            self.note_removed(id);
+            lost.push(self.label(id));
+        }
+
+        // Forward: what the withdrawn inputs used to block.
+        if settle {
+            loop {
+                let active = self.active_ids();
+                let new_facts = self.fuzzy_derivations(&active);
+                let ballots = self.ballots(&active);
+                if new_facts.is_empty() && ballots.is_empty() {
+                    break;
+                }
+                for (output_id, causes, value, _) in new_facts {
+                    self.commit(0, output_id, causes, value);
+                }
+                for ballot in ballots {
+                    let value = ballot
+                        .causes
+                        .iter()
+                        .map(|c| self.strength_of(*c))
+                        .fold(1.0, f32::min);
+                    self.commit(0, ballot.winner, ballot.causes, value);
+                }
+            }
+        }
+        self.blocked.clear();
+        self.flush_diff(0);
+
+        lost.sort();
+        for label in &lost {
+            self.report(|r| r.lost(label));
+        }
+        if settle {
+            self.report(|r| r.settled());
+        }
+        lost
+    }
+
+    /// Withdraws every conclusion that no rule, vote or custom rule would
+    /// draw from memory as it is now, with forbids read against all of it,
+    /// and then what rested on them. `retract` reads a forbid only once the
+    /// sweep has re-justified it; here a forbid derived after the flag it
+    /// inhibits counts too, which is what lets negation through a cycle
+    /// (see `validate`) alternate. Latched inputs still count as triggers.
+    /// Unblocked conclusions are left to the next tick. Returns whether
+    /// anything was withdrawn.
+    pub(crate) fn withdraw_inhibited(&mut self) -> bool {
+        let active = self.active_ids();
+        let support = active.union(&self.latched);
+        let custom: Vec<FlagId> = self
+            .custom_fired(&support)
+            .into_iter()
+            .map(|(out, _)| out)
+            .collect();
+        let mut inhibited: Vec<FlagId> = self
+            .active_memory
+            .iter()
+            .filter(|(_, source)| matches!(source, Source::Derived { .. }))
+            .map(|(id, _)| *id)
+            .filter(|id| {
+                let drawn = self.rules.iter().any(|rule| {
+                    rule.output == *id
+                        && self.triggered(rule, &support)
+                        && self.blockers(rule, &active).is_empty()
+                });
+                let voted = self
+                    .votes
+                    .iter()
+                    .any(|vote| vote.candidates.iter().any(|(out, _)| out == id));
+                !drawn && !voted && !custom.contains(id)
+            })
+            .collect();
+        if inhibited.is_empty() {
+            return false;
+        }
+        inhibited.sort();
+        for &id in &inhibited {
+            self.active_memory.remove(&id);
+            self.note_removed(id);
+            let label = self.label(id);
+            self.report(|r| r.lost(&label));
+        }
+        self.reconcile(false);
+        true
+    }
+
+    /// Replaces the justifications of the derived flag `id`; each one it
+    /// did not have before counts towards `activation_multiplicity`.
+    fn rejustify(&mut self, id: FlagId, justifications: Vec<Vec<FlagId>>) {
+        let gained = match self.active_memory.get(&id) {
+            Some(Source::Derived {
+                justifications: old,
+            }) => justifications
+                .iter()
+                .filter(|causes| !old.contains(causes))
+                .count(),
+            _ => justifications.len(),
+        };
+        *self.activations.entry(id).or_default() += gained;
+        self.active_memory
+            .insert(id, Source::Derived { justifications });
+    }
+
+    /// Justifications of the derived flag `id` using `supported` flags only:
+    /// the recorded ones whose rule still holds, in their order, then those of
+    /// other satisfied rules producing it. Vote winners keep their recorded
+    /// causes while those hold. `None` if nothing supports it any more.
+    fn justifications(&self, id: FlagId, supported: &FlagSet) -> Option<Vec<Vec<FlagId>>> {
+        let Some(Source::Derived {
+            justifications: recorded,
+        }) = self.active_memory.get(&id)
+        else {
+            return None;
+        };
+
+        let mut options: Vec<Vec<FlagId>> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.output == id && self.satisfied(rule, supported))
+            .map(|rule| self.causes(rule, supported))
+            .collect();
+        options.extend(
+            self.custom_fired(supported)
+                .into_iter()
+                .filter(|(out, _)| *out == id)
+                .map(|(_, causes)| causes),
+        );
+        let voted = self
+            .votes
+            .iter()
+            .any(|vote| vote.candidates.iter().any(|(out, _)| *out == id));
+
+        let mut kept: Vec<Vec<FlagId>> = recorded
+            .iter()
+            .filter(|causes| {
+                options.contains(causes) || (voted && causes.iter().all(|c| supported.contains(c)))
+            })
+            .cloned()
+            .collect();
+        for causes in options {
+            if !kept.contains(&causes) {
+                kept.push(causes);
+            }
+        }
+        (!kept.is_empty()).then_some(kept)
+    }
+
+    // ========================================================================
+    // Queries (Programmatic Counterpart to `trace`)
+    // ========================================================================
+
+    /// Whether `label` is currently active. Unknown labels are not.
+    pub fn is_active(&self, label: &str) -> bool {
+        self.symbols
+            .get(label)
+            .is_some_and(|id| self.active_memory.contains_key(&id))
+    }
+
+    /// The tick that activated `label`, counted across the whole mind: the
+    /// first tick after `new` or `reset_memory` is 1, and every later tick
+    /// of any `ponder`, `tick` or decay loop counts on from there, whatever
+    /// `tick_count` it was given. 0 for inputs and for flags derived
+    /// outside a tick, e.g. by `retract`.
+    /// `None` if it is unknown or inactive. Alternative justifications
+    /// found later keep the first tick.
+    pub fn derived_at(&self, label: &str) -> Option<usize> {
+        let id = self.symbols.get(label)?;
+        self.active_memory
+            .contains_key(&id)
+            .then(|| self.activated_at.get(&id).copied().unwrap_or(0))
+    }
+
+    /// Why `label` is active, or `None` if it is unknown or inactive.
+    pub fn source_of(&self, label: &str) -> Option<&Source> {
+        self.active_memory.get(&self.symbols.get(label)?)
+    }
+
+    /// `True`, `False` or `Unknown`; an unknown label is `Unknown`.
+    pub fn state(&self, label: &str) -> FlagState {
+        match self.symbols.get(label) {
+            Some(id) if self.active_memory.contains_key(&id) => FlagState::True,
+            Some(id) if self.known_false.contains(&id) => FlagState::False,
+            _ => FlagState::Unknown,
+        }
+    }
+
+    /// Labels asserted false that were (or are) also true, sorted: an
+    /// `inject_false` refused because the flag was active, or an injection
+    /// or derivation that overrode an `inject_false`. Cleared by `reset_memory`.
+    pub fn false_conflicts(&self) -> Vec<String> {
+        let mut labels: Vec<String> = self
+            .false_conflicts
+            .iter()
+            .map(|id| self.label(*id))
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Labels of every active flag, sorted.
+    pub fn active_labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = self
+            .active_memory
+            .keys()
+            .map(|id| self.label(*id))
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Labels of the active flags that were injected, sorted: the axioms.
+    /// Injecting just these into a fresh mind re-derives `derived`.
+    pub fn inputs(&self) -> Vec<String> {
+        self.labels_where(|source| matches!(source, Source::Input))
+    }
+
+    /// Labels of the active flags some rule or vote concluded, sorted.
+    pub fn derived(&self) -> Vec<String> {
+        self.labels_where(|source| matches!(source, Source::Derived { .. }))
+    }
+
+    /// Sorted labels of the active flags whose source passes `keep`.
+    fn labels_where(&self, keep: impl Fn(&Source) -> bool) -> Vec<String> {
+        let mut labels: Vec<String> = self
+            .active_memory
+            .iter()
+            .filter(|(_, source)| keep(source))
+            .map(|(id, _)| self.label(*id))
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Every active flag with its source, by ascending id. Lazy: nothing is
+    /// collected, so a single scan costs no allocation.
+    pub fn iter_active(&self) -> impl Iterator<Item = (FlagId, &Source)> {
+        (1..=self.symbols.len() as FlagId)
+            .filter_map(|id| self.active_memory.get(&id).map(|source| (id, source)))
+    }
+
+    /// `iter_active` by label (still in id order, not sorted by label).
+    pub fn iter_active_labels(&self) -> impl Iterator<Item = (&str, &Source)> {
+        self.iter_active()
+            .filter_map(|(id, source)| Some((self.symbols.label_str(id)?, source)))
+    }
+
+    /// How many distinct derivations of `label` were recorded since the last
+    /// `reset_memory`: one per justification it gained, whether in the tick
+    /// that first derived it, as a later tick's alternative, or through
+    /// truth maintenance. A rule firing again on the same causes adds none.
+    pub fn activation_multiplicity(&self, label: &str) -> usize {
+        self.symbols
+            .get(label)
+            .and_then(|id| self.activations.get(&id))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    #[test]
+    fn retract_keeps_the_activation_threshold() {
+        let mut mind = quiet();
+        mind.set_activation_threshold(0.5);
+        mind.learn(&["A"], "C");
+        mind.inject_weighted(&[("A", 0.3)]);
+        mind.inject(&["Z"]);
+        mind.ponder();
+        assert!(!mind.is_active("C"));
+        mind.retract(&["Z"]);
+        assert!(!mind.is_active("C"));
+    }
+
+    #[test]
+    fn retract_keeps_min_confidence() {
+        let mut mind = quiet();
+        mind.rule_fuzzy(&["A"], "C", 0.8);
+        mind.inject_weighted(&[("A", 0.3)]);
+        mind.inject(&["Z"]);
+        mind.ponder();
+        assert!(!mind.is_active("C"));
+        mind.retract(&["Z"]);
+        assert!(!mind.is_active("C"));
+
+        mind.inject_weighted(&[("A", 0.9)]);
+        mind.ponder();
+        assert_eq!(mind.confidence("C"), Some(0.9));
+    }
+
+    #[test]
+    fn a_strict_rule_waits_until_its_forbid_is_known_false() {
+        let mut mind = quiet();
+        mind.rule(&["SwitchOn"], &["PowerOutage"], "LightOn");
+        mind.rule_strict(&["SwitchOn"], &["PowerOutage"], "LightVerified");
+        mind.inject(&["SwitchOn"]);
+        mind.ponder();
+        assert!(mind.is_active("LightOn"));
+        assert!(!mind.is_active("LightVerified"));
+
+        mind.inject_false(&["PowerOutage"]);
+        mind.ponder();
+        assert!(mind.is_active("LightVerified"));
+    }
+
+    #[test]
+    fn injecting_the_inputs_alone_re_derives_the_rest() {
+        let mut mind = quiet();
+        mind.learn(&["KeyCard", "Fingerprint"], "AccessGranted");
+        mind.learn(&["AccessGranted"], "DoorOpen");
+        mind.inject(&["KeyCard", "Fingerprint"]);
+        mind.ponder();
+        assert_eq!(mind.inputs(), ["Fingerprint", "KeyCard"]);
+        assert_eq!(mind.derived(), ["AccessGranted", "DoorOpen"]);
+
+        let inputs = mind.inputs();
+        let mut fresh = mind.clone();
+        fresh.reset_memory();
+        fresh.inject(&inputs.iter().map(String::as_str).collect::<Vec<_>>());
+        fresh.ponder();
+        assert_eq!(fresh.derived(), mind.derived());
+
+        mind.reset_derivations();
+        assert!(mind.derived().is_empty());
+        mind.ponder();
+        assert_eq!(mind.derived(), fresh.derived());
+    }
+
+    #[test]
+    fn min_confidence_decides_whether_a_fuzzy_rule_fires() {
+        let run = |min_confidence: f32| {
+            let mut mind = quiet();
+            mind.rule_fuzzy(&["Smoke", "Heat"], "Fire", min_confidence);
+            mind.inject_weighted(&[("Smoke", 0.7), ("Heat", 0.95)]);
+            mind.ponder();
+            mind.confidence("Fire")
+        };
+        assert_eq!(run(0.5), Some(0.7)); // The weakest trigger clears the bar
+        assert_eq!(run(0.9), None);
+    }
+
+    fn lights() -> Mind {
+        let mut mind = quiet();
+        mind.rule(&["Switch"], &["PowerOutage"], "LightOn");
+        mind.learn(&["LightOn"], "RoomLit");
+        mind
+    }
+
+    fn fresh(inputs: &[&str]) -> Vec<String> {
+        let mut mind = lights();
+        mind.inject(inputs);
+        mind.ponder();
+        mind.active_labels()
+    }
+
+    #[test]
+    fn set_input_matches_a_fresh_run() {
+        let mut mind = lights();
+        mind.set_input("Switch", true);
+        assert!(mind.is_active("LightOn"));
+        assert_eq!(mind.active_labels(), fresh(&["Switch"]));
+
+        mind.set_input("PowerOutage", true);
+        assert!(!mind.is_active("LightOn"));
+        assert!(!mind.is_active("RoomLit"));
+        assert_eq!(mind.active_labels(), fresh(&["Switch", "PowerOutage"]));
+
+        mind.set_input("PowerOutage", false);
+        assert!(mind.is_active("LightOn"));
+        assert!(mind.is_active("RoomLit"));
+        assert_eq!(mind.active_labels(), fresh(&["Switch"]));
+    }
+
+    struct Log(std::sync::mpsc::Sender<String>);
+
+    impl crate::report::Reporter for Log {
+        fn derived(&mut self, tick: usize, causes: &[String], output: &str, value: f32) {
+            let _ = self
+                .0
+                .send(format!("{tick}: {causes:?} -> {output} ({value})"));
+        }
+        fn vote(&mut self, tick: usize, scores: &[(String, f32)]) {
+            let _ = self.0.send(format!("{tick}: vote {scores:?}"));
+        }
+        fn settled(&mut self) {
+            let _ = self.0.send("settled".into());
+        }
+    }
+
+    #[test]
+    fn runs_log_byte_for_byte_the_same() {
+        let run = |warm_up: &[&str]| {
+            let mut mind = Mind::new();
+            for label in warm_up {
+                mind.id(label); // Other ids, same rules
+            }
+            let (sink, log) = std::sync::mpsc::channel();
+            mind.set_reporter(Log(sink));
+            mind.learn(&["Dough", "Oven"], "Bread");
+            mind.learn(&["Dough", "Pan"], "Crepe");
+            mind.learn(&["Bread", "Butter"], "Toast");
+            mind.learn(&["Crepe", "Butter"], "Breakfast");
+            mind.inject(&["Dough", "Oven", "Pan", "Butter"]);
+            mind.ponder();
+            let mut out: Vec<String> = log.try_iter().collect();
+            out.push(mind.decision_report());
+            out.join("\n")
+        };
+        let first = run(&[]);
+        assert!(first.contains("-> Toast"), "{first}");
+        assert_eq!(first, run(&[]));
+        assert_eq!(first, run(&["Toast", "Breakfast", "Crepe", "Bread"]));
+    }
+
+    #[test]
+    fn the_higher_priority_rule_inhibits_the_lower() {
+        let settle = |brake: i32, throttle: i32| {
+            let mut mind = quiet();
+            mind.rule_with_priority(&["Go"], &["Run"], "Stop", brake);
+            mind.rule_with_priority(&["Go"], &["Stop"], "Run", throttle);
+            mind.inject(&["Go"]);
+            mind.ponder();
+            mind.active_labels()
+        };
+        assert_eq!(settle(1, 0), ["Go", "Stop"]);
+        assert_eq!(settle(0, 1), ["Go", "Run"]);
+        // One level sees the same memory: both fire
+        assert_eq!(settle(0, 0), ["Go", "Run", "Stop"]);
+    }
+
+    #[test]
+    fn heavier_rules_commit_first() {
+        let mut mind = quiet();
+        let (sink, log) = std::sync::mpsc::channel();
+        mind.set_reporter(Log(sink));
+        mind.rule_with_weight(&["A"], &[], "Light", 0.2);
+        mind.rule_with_weight(&["A"], &[], "Heavy", 0.9);
+        mind.rule_with_weight(&["A"], &[], "Both", 0.2);
+        mind.rule_with_weight(&["B"], &[], "Both", 0.9);
+        mind.inject(&["A", "B"]);
+        mind.tick(1);
+        let order: Vec<String> = log.try_iter().collect();
+        assert_eq!(
+            order,
+            [
+                "1: [\"A\"] -> Heavy (1)",
+                "1: [\"B\"] -> Both (1)",
+                "1: [\"A\"] -> Light (1)",
+                "1: [\"A\"] -> Both (1)",
+            ]
+        );
+        let both = mind.symbols.get("Both").unwrap();
+        let b = mind.symbols.get("B").unwrap();
+        assert_eq!(mind.active_memory[&both].causes(), [b]);
+    }
+
+    #[test]
+    fn unlearn_removes_only_the_learned_rule() {
+        let mut mind = quiet();
+        mind.rule_or(&[], &["Rain", "Snow"], &[], "Wet");
+        mind.rule(&["Hose"], &["Dry"], "Wet");
+        mind.learn(&["Hose"], "Wet");
+        mind.learn(&["Spill"], "Wet");
+
+        assert!(!mind.unlearn(&[], "Wet"));
+        assert!(mind.unlearn(&["Hose"], "Wet"));
+        assert!(!mind.unlearn(&["Hose"], "Wet"));
+        assert_eq!(mind.rules.len(), 3);
+
+        mind.inject(&["Snow", "Dry", "Spill"]);
+        mind.ponder();
+        let wet = mind.symbols.get("Wet").unwrap();
+        let justifications = match &mind.active_memory[&wet] {
+            Source::Derived { justifications } => justifications.len(),
+            Source::Input => 0,
+        };
+        assert_eq!(justifications, 2);
+    }
+
+    #[test]
+    fn multiplicity_counts_later_alternatives() {
+        let mut mind = quiet();
+        mind.learn(&["Rain"], "Wet");
+        mind.learn(&["Rain"], "Cloudy");
+        mind.learn(&["Cloudy"], "Wet");
+        mind.learn(&["Hose"], "Wet");
+        mind.inject(&["Rain"]);
+        mind.ponder();
+        // Rain in tick 1, Cloudy in tick 2
+        assert_eq!(mind.activation_multiplicity("Wet"), 2);
+        assert_eq!(mind.activation_multiplicity("Cloudy"), 1);
+
+        mind.inject(&["Hose"]);
+        mind.ponder();
+        assert_eq!(mind.activation_multiplicity("Wet"), 3);
+        mind.ponder();
+        assert_eq!(mind.activation_multiplicity("Wet"), 3);
+
+        mind.reset_memory();
+        assert_eq!(mind.activation_multiplicity("Wet"), 0);
+    }
+
+    #[test]
+    fn multiplicity_counts_justifications_truth_maintenance_finds() {
+        let mut mind = quiet();
+        mind.learn(&["Rain"], "Wet");
+        mind.learn(&["Hose"], "Wet");
+        mind.rule(&["Dew"], &["Sun"], "Wet");
+        mind.inject(&["Rain", "Hose", "Sun", "Dew"]);
+        mind.ponder();
+        assert_eq!(mind.activation_multiplicity("Wet"), 2);
+
+        // `Hose` still holds: the justification was counted already
+        mind.retract(&["Rain"]);
+        assert!(mind.is_active("Wet"));
+        assert_eq!(mind.activation_multiplicity("Wet"), 2);
+
+        // Without `Sun`, `Dew` justifies `Wet` for the first time
+        mind.retract(&["Sun"]);
+        assert_eq!(mind.activation_multiplicity("Wet"), 3);
+    }
+
+    #[test]
+    fn output_thresholds_replace_the_global_one() {
+        let mut mind = quiet();
+        mind.set_activation_threshold(0.5);
+        mind.set_output_threshold("Diagnosis", 0.8);
+        mind.set_output_threshold("Hint", 0.3);
+        mind.learn(&["Symptom"], "Diagnosis");
+        mind.learn(&["Symptom"], "Hint");
+        mind.learn(&["Symptom"], "Note");
+        mind.learn(&["Symptom"], "Memo");
+        mind.set_output_threshold("Memo", 0.4);
+        mind.rule_fuzzy(&["Symptom"], "Memo2", 0.9);
+        mind.set_output_threshold("Memo2", 0.1);
+
+        mind.inject_weighted(&[("Symptom", 0.4)]);
+        mind.ponder();
+        assert!(!mind.is_active("Diagnosis"));
+        assert!(mind.is_active("Hint"));
+        assert!(!mind.is_active("Note")); // Global: 0.4 is not above 0.5
+        assert!(!mind.is_active("Memo")); // Own bar is exclusive too
+        assert!(!mind.is_active("Memo2")); // min_confidence still holds
+
+        mind.inject_weighted(&[("Symptom", 0.9)]);
+        mind.ponder();
+        assert_eq!(mind.confidence("Diagnosis"), Some(0.9));
+        assert!(mind.is_active("Note"));
+        assert!(mind.is_active("Memo"));
+    }
+
+    #[test]
+    fn an_output_threshold_is_not_met_at_its_own_value() {
+        let run = |threshold: f32, value: f32| {
+            let mut mind = quiet();
+            mind.set_output_threshold("Hint", threshold);
+            mind.learn(&["Symptom"], "Hint");
+            mind.inject_weighted(&[("Symptom", value)]);
+            mind.ponder();
+            mind.is_active("Hint")
+        };
+        assert!(!run(0.5, 0.5));
+        assert!(run(0.5, 0.51));
+        assert!(!run(0.0, 0.0));
+        assert!(run(0.0, 0.01));
+    }
+
+    #[test]
+    fn rule_edits_are_versioned_and_logged() {
+        use crate::rules::RuleChangeKind::*;
+        let mut mind = quiet();
+        mind.learn(&["Smoke"], "Alarm");
+        mind.rule_with_priority(&["Heat"], &[], "Alarm", 2);
+        mind.learn_in_group("night", &["Motion"], "Lamp");
+        mind.set_group_enabled("night", false);
+        mind.set_group_enabled("night", false); // No change, no version
+        assert!(mind.unlearn(&["Smoke"], "Alarm"));
+
+        let changelog = mind.rule_changelog();
+        let log: Vec<(u64, usize, &str, RuleChangeKind)> = changelog
+            .iter()
+            .map(|c| (c.version, c.rule, c.output.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            log,
+            [
+                (1, 0, "Alarm", Added),
+                (2, 1, "Alarm", Added),
+                (3, 2, "Lamp", Added),
+                (4, 2, "Lamp", Disabled),
+                (5, 0, "Alarm", Removed),
+            ]
+        );
+        assert_eq!(mind.rule_version(), 5);
+        let stamps: Vec<(u64, u64)> = mind
+            .rules
+            .iter()
+            .map(|rule| (rule.added_at, rule.modified_at))
+            .collect();
+        assert_eq!(stamps, [(2, 2), (3, 4)]);
+
+        // Merging a rule it already has adds no version
+        let mut other = quiet();
+        other.rule_with_priority(&["Heat"], &[], "Alarm", 2);
+        assert_eq!(mind.merge(&other).skipped, 1);
+        assert_eq!(mind.rule_version(), 5);
+    }
+
+    #[test]
+    fn derived_at_counts_ticks_across_ponders() {
+        let mut mind = quiet();
+        mind.learn(&["Water"], "Wet");
+        mind.learn(&["Wet"], "Cold");
+        mind.learn(&["Wind"], "Storm");
+        mind.inject(&["Water"]);
+        mind.ponder(); // Ticks 1 to 3
+        mind.inject(&["Wind"]);
+        mind.ponder(); // Tick 4 derives Storm
+        assert_eq!(mind.derived_at("Wet"), Some(1));
+        assert_eq!(mind.derived_at("Cold"), Some(2));
+        assert_eq!(mind.derived_at("Storm"), Some(4));
+        assert_eq!(mind.derived_at("Wind"), Some(0));
+
+        mind.reset_memory();
+        mind.inject(&["Wind"]);
+        mind.ponder();
+        assert_eq!(mind.derived_at("Storm"), Some(1));
+    }
+
+    #[test]
+    fn unlearn_skips_grouped_and_tuned_rules() {
+        let mut mind = quiet();
+        mind.learn_in_group("night", &["Motion"], "Lamp");
+        mind.rule_fuzzy(&["Motion"], "Lamp", 0.5);
+        mind.rule_with_priority(&["Motion"], &[], "Lamp", 1);
+        mind.learn(&["Motion"], "Lamp");
+
+        assert!(mind.unlearn(&["Motion"], "Lamp"));
+        assert_eq!(mind.rules.len(), 3);
+        assert!(mind.rules.iter().all(|rule| !rule.is_plain()));
+        assert!(mind.rules[0].group.is_some());
+        assert!(!mind.unlearn(&["Motion"], "Lamp"));
+    }
+
+    #[test]
+    fn diffs_carry_the_running_tick_and_accumulate_until_taken() {
+        let mut mind = quiet();
+        mind.learn(&["Stove"], "Hot");
+        mind.learn(&["Hot"], "Boiling");
+        mind.learn(&["Lid"], "Steam");
+        let id = |mind: &Mind, label: &str| mind.symbols.get(label).unwrap();
+
+        mind.inject(&["Stove"]);
+        mind.ponder();
+        let first = mind.tick_diff();
+        assert_eq!(first.added, [id(&mind, "Boiling")]); // The last tick only
+        let taken = mind.take_diff();
+        assert_eq!(taken.tick, first.tick);
+        let mut all = vec![id(&mind, "Stove"), id(&mind, "Hot"), id(&mind, "Boiling")];
+        all.sort();
+        assert_eq!(taken.added, all);
+
+        mind.inject(&["Lid"]);
+        mind.ponder();
+        let second = mind.tick_diff();
+        assert!(second.tick > first.tick, "ponder restarts tick_count at 1");
+        let mut added = vec![id(&mind, "Lid"), id(&mind, "Steam")];
+        added.sort();
+        assert_eq!(mind.take_diff().added, added);
+        assert_eq!(mind.take_diff(), TickDiff::default());
+    }
+}
@@ -0,0 +1,83 @@
+//! Errors of the fallible public API. No library path panics on user input.
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::parse::ParseError;
+use crate::strata::LogicError;
+
+/// Why a `Mind` call could not do what was asked. Memory and rules are left
+/// exactly as they were.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MindError {
+    /// Writing a trace tree to stdout failed (e.g. a closed pipe).
+    Render(String),
+    /// Rule text did not parse (see `load_rules`).
+    Parse(ParseError),
+    /// The rules cannot be ordered, or did not settle (see `LogicError`).
+    Logic(LogicError),
+    /// A K-of-N rule needs `1 <= k <= inputs`.
+    InvalidThreshold {
+        output: String,
+        k: usize,
+        inputs: usize,
+    },
+    /// `synonym` already names a flag of its own (see `alias`).
+    AliasConflict { canonical: String, synonym: String },
+    /// `freeze_labels` on a mind that already has `count` ids.
+    LabelsAssigned { count: usize },
+    /// An exhaustive analysis (2^n fixpoints) was given more than `cap` inputs.
+    TooManyInputs {
+        analysis: &'static str,
+        given: usize,
+        cap: usize,
+    },
+}
+
+impl fmt::Display for MindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MindError::Render(reason) => write!(f, "cannot render trace: {}", reason),
+            MindError::Parse(err) => write!(f, "{}", err),
+            MindError::Logic(err) => write!(f, "{}", err),
+            MindError::InvalidThreshold { output, k, inputs } => write!(
+                f,
+                "rule `{}`: threshold {} must be between 1 and the {} input(s)",
+                output, k, inputs
+            ),
+            MindError::AliasConflict { canonical, synonym } => write!(
+                f,
+                "`{}` is a flag of its own and cannot become an alias of `{}`",
+                synonym, canonical
+            ),
+            MindError::LabelsAssigned { count } => write!(
+                f,
+                "{} label(s) already have ids; freeze labels on a fresh mind",
+                count
+            ),
+            MindError::TooManyInputs {
+                analysis,
+                given,
+                cap,
+            } => write!(
+                f,
+                "{}: {} inputs exceed the cap of {}",
+                analysis, given, cap
+            ),
+        }
+    }
+}
+
+impl core::error::Error for MindError {}
+
+impl From<ParseError> for MindError {
+    fn from(err: ParseError) -> Self {
+        MindError::Parse(err)
+    }
+}
+
+impl From<LogicError> for MindError {
+    fn from(err: LogicError) -> Self {
+        MindError::Logic(err)
+    }
+}
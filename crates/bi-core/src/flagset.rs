@@ -0,0 +1,150 @@
+//! Flag sets: active memory as a bitset for the hot membership checks.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::Map;
+
+use fixedbitset::{FixedBitSet, Ones};
+
+use crate::collections::HashSet;
+use crate::symbols::FlagId;
+
+/// A set of flags, one bit per id. Ids stay contiguous (the symbol table
+/// hands them out from 1 and never reuses one), so the bitset is as small as
+/// the vocabulary, and checking a rule's triggers costs a few word reads
+/// instead of a hash per trigger.
+#[derive(Clone, Default)]
+pub struct FlagSet(FixedBitSet);
+
+impl FlagSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, id: &FlagId) -> bool {
+        self.0.contains(*id as usize)
+    }
+
+    /// Whether `id` was newly added.
+    pub fn insert(&mut self, id: FlagId) -> bool {
+        let bit = id as usize;
+        if bit >= self.0.len() {
+            self.0.grow((bit + 1).next_power_of_two());
+        }
+        !self.0.put(bit)
+    }
+
+    /// Whether `id` was present.
+    pub fn remove(&mut self, id: &FlagId) -> bool {
+        let present = self.contains(id);
+        if present {
+            self.0.set(*id as usize, false);
+        }
+        present
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones(..)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.ones().next().is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Ids in ascending order.
+    pub fn iter(&self) -> <&Self as IntoIterator>::IntoIter {
+        self.into_iter()
+    }
+
+    pub fn is_subset(&self, other: &FlagSet) -> bool {
+        self.0.is_subset(&other.0)
+    }
+
+    /// Ids in `self` but not in `other`, ascending.
+    pub fn difference<'a>(&'a self, other: &'a FlagSet) -> impl Iterator<Item = FlagId> + 'a {
+        self.iter().filter(move |id| !other.contains(id))
+    }
+
+    /// Ids in either set, ascending.
+    pub fn union(&self, other: &FlagSet) -> FlagSet {
+        let mut union = self.clone();
+        union.0.union_with(&other.0);
+        union
+    }
+}
+
+impl PartialEq for FlagSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.symmetric_difference(&other.0).next().is_none() // Capacity aside
+    }
+}
+
+impl Eq for FlagSet {}
+
+impl fmt::Debug for FlagSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl Extend<FlagId> for FlagSet {
+    fn extend<I: IntoIterator<Item = FlagId>>(&mut self, ids: I) {
+        for id in ids {
+            self.insert(id);
+        }
+    }
+}
+
+impl<'a> Extend<&'a FlagId> for FlagSet {
+    fn extend<I: IntoIterator<Item = &'a FlagId>>(&mut self, ids: I) {
+        self.extend(ids.into_iter().copied());
+    }
+}
+
+impl FromIterator<FlagId> for FlagSet {
+    fn from_iter<I: IntoIterator<Item = FlagId>>(ids: I) -> Self {
+        let mut set = Self::new();
+        set.extend(ids);
+        set
+    }
+}
+
+impl<const N: usize> From<[FlagId; N]> for FlagSet {
+    fn from(ids: [FlagId; N]) -> Self {
+        ids.into_iter().collect()
+    }
+}
+
+impl From<HashSet<FlagId>> for FlagSet {
+    fn from(ids: HashSet<FlagId>) -> Self {
+        ids.into_iter().collect()
+    }
+}
+
+impl From<FlagSet> for HashSet<FlagId> {
+    fn from(set: FlagSet) -> Self {
+        set.iter().collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a FlagSet {
+    type Item = FlagId;
+    type IntoIter = Map<Ones<'a>, fn(usize) -> FlagId>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.ones().map(|bit| bit as FlagId)
+    }
+}
+
+impl IntoIterator for FlagSet {
+    type Item = FlagId;
+    type IntoIter = alloc::vec::IntoIter<FlagId>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().collect::<Vec<_>>().into_iter()
+    }
+}
@@ -0,0 +1,138 @@
+//! Rule Groups: sets of rules switched on and off between ticks.
+//!
+//! A disabled group keeps its rules; `tick` just passes over them, as do
+//! `settle` and the analyses built on it. Flags a group derived while it
+//! was enabled stay active. Ungrouped rules always take part. A group can
+//! also be gated by a flag, so that reasoning itself switches it.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::engine::Mind;
+use crate::flagset::FlagSet;
+use crate::rules::{Rule, RuleChangeKind};
+use crate::symbols::FlagId;
+
+impl<T> Mind<T> {
+    /// `learn` into `group`: triggers -> output while the group is enabled.
+    pub fn learn_in_group(&mut self, group: &str, triggers: &[&str], output: &str) {
+        self.rule_in_group(group, triggers, &[], output);
+    }
+
+    /// `rule` into `group`: (triggers) - (forbids) -> output while the
+    /// group is enabled. Groups are created enabled on first use.
+    pub fn rule_in_group(
+        &mut self,
+        group: &str,
+        triggers: &[&str],
+        forbids: &[&str],
+        output: &str,
+    ) {
+        self.rule(triggers, forbids, output);
+        let group = self.group_id(group);
+        let index = self.rules.len() - 1;
+        self.rules[index].group = Some(group);
+        if let Some(&gate) = self.group_gates.get(&group) {
+            let gated = self.trigger_index.entry(gate).or_default();
+            if !gated.contains(&index) {
+                gated.push(index);
+            }
+        }
+    }
+
+    /// Let `by_flag` switch `group`: its rules take part in `tick` only
+    /// while `by_flag` is active, on top of `set_group_enabled`. Derived
+    /// flags can so reconfigure which rules run. Replaces any earlier gate.
+    ///
+    /// The gate counts as one more trigger of each rule in the group: it is
+    /// among the causes of what the group derives, the rules run from the
+    /// tick after it becomes active, and their stratum is at least the
+    /// gate's. Being positive, a gate never affects stratified negation;
+    /// gating on an absent flag would be a forbid, so it is not offered.
+    /// As `tick` never removes flags, a group opened mid-`ponder` stays open
+    /// until the gate is retracted. Gates carry over in `merge` but are not
+    /// part of `export_rules`.
+    pub fn gate_group(&mut self, group: &str, by_flag: &str) {
+        let group = self.group_id(group);
+        let gate = self.id(by_flag);
+        self.group_gates.insert(group, gate);
+        self.reindex(); // The gate now belongs to the rules it opens
+    }
+
+    /// Drops the gate of `group`; its rules run whenever it is enabled.
+    pub fn ungate_group(&mut self, group: &str) {
+        let group = self.group_id(group);
+        if self.group_gates.remove(&group).is_some() {
+            self.reindex();
+        }
+    }
+
+    /// Switch every rule of `group` on or off from the next tick on.
+    /// Re-enabled rules are re-evaluated against the memory as it is then.
+    pub fn set_group_enabled(&mut self, group: &str, enabled: bool) {
+        let group = self.group_id(group);
+        let changed = if enabled {
+            self.disabled_groups.remove(&group)
+        } else {
+            self.disabled_groups.insert(group)
+        };
+        if changed {
+            let members: Vec<usize> = (0..self.rules.len())
+                .filter(|&index| self.rules[index].group == Some(group))
+                .collect();
+            let kind = if enabled {
+                RuleChangeKind::Enabled
+            } else {
+                RuleChangeKind::Disabled
+            };
+            self.note_rule_edit(&members, kind);
+            self.dirty_rules.extend(members);
+        }
+    }
+
+    /// Whether `group` is enabled; unknown groups are.
+    pub fn group_enabled(&self, group: &str) -> bool {
+        match self.group_names.iter().position(|name| name == group) {
+            Some(group) => !self.disabled_groups.contains(&group),
+            None => true,
+        }
+    }
+
+    /// Every group name, in order of first use.
+    pub fn groups(&self) -> &[String] {
+        &self.group_names
+    }
+
+    /// Id of `group`, registered on first use.
+    pub(crate) fn group_id(&mut self, group: &str) -> usize {
+        match self.group_names.iter().position(|name| name == group) {
+            Some(id) => id,
+            None => {
+                self.group_names.push(group.to_string());
+                self.group_names.len() - 1
+            }
+        }
+    }
+
+    /// Whether `rule` is not in a group switched off by `set_group_enabled`.
+    pub(crate) fn switched_on(&self, rule: &Rule) -> bool {
+        match rule.group {
+            Some(group) => !self.disabled_groups.contains(&group),
+            None => true,
+        }
+    }
+
+    /// The flag gating the group of `rule`, if any (see `gate_group`).
+    pub(crate) fn gate(&self, rule: &Rule) -> Option<FlagId> {
+        rule.group
+            .and_then(|group| self.group_gates.get(&group))
+            .copied()
+    }
+
+    /// Whether the rule at `index` takes part in inference against `active`:
+    /// switched on, and its gate (if any) active.
+    pub(crate) fn rule_enabled(&self, index: usize, active: &FlagSet) -> bool {
+        let rule = &self.rules[index];
+        self.switched_on(rule) && self.gate(rule).is_none_or(|gate| active.contains(&gate))
+    }
+}
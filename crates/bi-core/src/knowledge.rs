@@ -0,0 +1,185 @@
+//! Knowledge Files: rule bases as TOML documents (`toml` feature).
+//!
+//! ```toml
+//! [[rule]]
+//! triggers = ["Sharp", "Solid", "Cut"]
+//! output = "Separation"
+//!
+//! [[rule]]
+//! triggers = ["SwitchOn"]
+//! forbids = ["PowerOutage"]
+//! output = "LightOn"
+//! ```
+//!
+//! Each `[[rule]]` takes the fields of a `RuleSpec` (all optional but
+//! `output`), each `[[vote]]` those of a `VoteSpec`. An optional top-level
+//! `labels` array fixes the id order, as `export_toml` writes it.
+
+use core::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Mind;
+use crate::error::MindError;
+use crate::persist::{RuleSet, RuleSpec, VoteSpec};
+
+/// Why `load_from_path` learned nothing. Lines and columns start at 1.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadError {
+    /// The file could not be read.
+    Io { path: PathBuf, message: String },
+    /// The file is not a valid knowledge file.
+    Syntax {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The rules parsed but were refused (see `import_rules`).
+    Rules { path: PathBuf, error: MindError },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io { path, message } => write!(f, "{}: {}", path.display(), message),
+            LoadError::Syntax {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+            LoadError::Rules { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl core::error::Error for LoadError {}
+
+/// The document layout: `RuleSet` with TOML-friendly table names.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default)]
+struct KnowledgeFile {
+    labels: Vec<String>,
+    #[serde(rename = "rule")]
+    rules: Vec<RuleSpec>,
+    #[serde(rename = "vote")]
+    votes: Vec<VoteSpec>,
+}
+
+impl<T> Mind<T> {
+    /// Reads the knowledge file at `path` and learns its rules and votes
+    /// after the ones this mind already has, resolving labels through `id`.
+    /// Returns how many rules were loaded. On error nothing is learned.
+    pub fn load_from_path(&mut self, path: &Path) -> Result<usize, LoadError> {
+        let src = std::fs::read_to_string(path).map_err(|err| LoadError::Io {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        let file: KnowledgeFile = toml::from_str(&src).map_err(|err| {
+            let (line, column) = err.span().map_or((1, 1), |span| position(&src, span.start));
+            LoadError::Syntax {
+                path: path.to_path_buf(),
+                line,
+                column,
+                message: err.message().to_string(),
+            }
+        })?;
+
+        let count = file.rules.len();
+        let set = RuleSet {
+            labels: file.labels,
+            rules: file.rules,
+            votes: file.votes,
+        };
+        self.import_rules(set).map_err(|error| LoadError::Rules {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        Ok(count)
+    }
+
+    /// `export_rules` as a knowledge file; `load_from_path` on a fresh mind
+    /// reads it back to the same ids and the same inference.
+    pub fn export_toml(&self) -> String {
+        let set = self.export_rules();
+        let file = KnowledgeFile {
+            labels: set.labels,
+            rules: set.rules,
+            votes: set.votes,
+        };
+        toml::to_string(&file).unwrap_or_default() // Plain strings and numbers always serialize
+    }
+}
+
+/// Line and column of the byte `offset` in `src`.
+fn position(src: &str, offset: usize) -> (usize, usize) {
+    let before = &src[..offset.min(src.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    /// `src` written to a scratch file named after `name`.
+    fn scratch(name: &str, src: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("bi-core-{}.toml", name));
+        std::fs::write(&path, src).unwrap();
+        path
+    }
+
+    #[test]
+    fn a_loaded_file_runs_and_exports_back_to_the_same_rules() {
+        let path = scratch(
+            "knowledge-round-trip",
+            concat!(
+                "[[rule]]\ntriggers = [\"Sharp\", \"Solid\", \"Cut\"]\noutput = \"Separation\"\n\n",
+                "[[rule]]\ntriggers = [\"SwitchOn\"]\nforbids = [\"PowerOutage\"]\noutput = \"LightOn\"\n",
+            ),
+        );
+        let mut loaded = quiet();
+        assert_eq!(loaded.load_from_path(&path), Ok(2));
+        loaded.inject(&["SwitchOn"]);
+        loaded.ponder();
+        assert_eq!(loaded.active_labels(), ["LightOn", "SwitchOn"]);
+
+        std::fs::write(&path, loaded.export_toml()).unwrap();
+        let mut reloaded = quiet();
+        reloaded.load_from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded.rules(), loaded.rules());
+    }
+
+    #[test]
+    fn a_syntax_error_names_its_line_and_column() {
+        let path = scratch(
+            "knowledge-broken",
+            "[[rule]]\ntriggers = [\"Sharp\"\noutput = \"Cut\"\n",
+        );
+        let err = quiet().load_from_path(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(
+                err,
+                LoadError::Syntax {
+                    line: 3,
+                    column: 1,
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Boiled Water core: the Flag inference engine.
+//!
+//! A [`Mind`] holds a static rule graph (long-term memory) and the set of
+//! currently active flags (short-term memory). Inputs are injected, rules are
+//! applied tick by tick until nothing new can be derived, and every derived
+//! flag remembers the causes it was reasoned from. The everyday names are
+//! also gathered in [`prelude`].
+//!
+//! ```
+//! use bi_core::Mind;
+//!
+//! let mut mind = Mind::new();
+//! mind.learn(&["Knife"], "Sharp");
+//! mind.inject(&["Knife"]);
+//! mind.ponder();
+//! assert!(mind.explain("Sharp").is_some());
+//! ```
+//!
+//! Without the default `std` feature the crate is `#![no_std]` and needs
+//! only `alloc`: everything but the terminal, file and thread helpers.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod analysis;
+pub mod builder;
+pub mod check;
+pub mod clock;
+mod collections;
+pub mod compiled;
+pub mod custom;
+pub mod decay;
+pub mod engine;
+pub mod error;
+pub mod flagset;
+pub mod groups;
+#[cfg(feature = "toml")]
+pub mod knowledge;
+mod lock;
+pub mod observe;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod parse;
+pub mod payload;
+pub mod persist;
+pub mod prelude;
+pub mod prove;
+#[cfg(feature = "std")]
+pub mod replay;
+pub mod report;
+pub mod rules;
+pub mod snapshot;
+pub mod spread;
+pub mod stats;
+pub mod strata;
+pub mod stream;
+pub mod symbols;
+pub mod sync;
+pub mod trace;
+
+pub use analysis::{Contradiction, ExclusivityReason, Outcome};
+pub use builder::{BuildError, MindBuilder};
+pub use check::{CaseFailure, TestCase};
+pub use compiled::CompiledPlan;
+pub use decay::{FixpointOutcome, TriggerMode};
+pub use engine::{FlagState, Mind, Source};
+pub use error::MindError;
+pub use flagset::FlagSet;
+#[cfg(feature = "toml")]
+pub use knowledge::LoadError;
+pub use parse::ParseError;
+pub use persist::{MergeReport, RuleSet, RuleSpec, VoteSpec};
+#[cfg(feature = "std")]
+pub use replay::ReplayError;
+#[cfg(feature = "std")]
+pub use report::ConsoleReporter;
+#[cfg(feature = "tracing")]
+pub use report::TracingReporter;
+pub use report::{LogLayout, NullReporter, Reporter};
+pub use rules::{Rule, RuleChange, RuleChangeKind, Vote};
+#[cfg(feature = "std")]
+pub use snapshot::MemoryFileError;
+pub use snapshot::{MemoryDiff, MemorySnapshot};
+pub use stats::{Introspection, IoMatrix, Profiler, Stats};
+pub use strata::LogicError;
+pub use symbols::{FlagId, NAMESPACE_SEPARATOR, Normalizer, SymbolTable, namespace_of, qualify};
+pub use sync::TickDiff;
+pub use trace::{
+    AbsenceReport, DualExplanation, Explanation, NodeKind, RuleFailure, summarize, trace_patch,
+};
@@ -0,0 +1,108 @@
+//! The mutex that keeps `Mind: Sync`: std's with the `std` feature, a spin
+//! lock on an atomic flag without. Minds lock only to hand out one event
+//! at a time, so the lock is never held long.
+
+#[cfg(not(feature = "std"))]
+use core::cell::UnsafeCell;
+#[cfg(not(feature = "std"))]
+use core::ops::Deref;
+use core::ops::DerefMut;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::sync::{Mutex, PoisonError};
+
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub(crate) struct Lock<T: ?Sized>(Mutex<T>);
+
+#[cfg(feature = "std")]
+impl<T> Lock<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self(Mutex::new(value))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Lock<T> {
+    /// The value, even if a holder panicked before.
+    pub(crate) fn lock(&self) -> impl DerefMut<Target = T> + '_ {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The value without locking; `None` if a holder panicked before.
+    pub(crate) fn get_mut(&mut self) -> Option<&mut T> {
+        self.0.get_mut().ok()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+#[derive(Default)]
+pub(crate) struct Lock<T: ?Sized> {
+    held: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// The flag hands the value to one holder at a time, as a `Mutex` does.
+#[cfg(not(feature = "std"))]
+unsafe impl<T: ?Sized + Send> Send for Lock<T> {}
+#[cfg(not(feature = "std"))]
+unsafe impl<T: ?Sized + Send> Sync for Lock<T> {}
+
+#[cfg(not(feature = "std"))]
+impl<T> Lock<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            held: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> Lock<T> {
+    /// The value, once no one else holds it.
+    pub(crate) fn lock(&self) -> impl DerefMut<Target = T> + '_ {
+        while self
+            .held
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        Guard(self)
+    }
+
+    /// The value without locking.
+    pub(crate) fn get_mut(&mut self) -> Option<&mut T> {
+        Some(self.value.get_mut())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+struct Guard<'a, T: ?Sized>(&'a Lock<T>);
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> Deref for Guard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: `held` is ours until the guard drops.
+        unsafe { &*self.0.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> DerefMut for Guard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: `held` is ours until the guard drops.
+        unsafe { &mut *self.0.value.get() }
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<T: ?Sized> Drop for Guard<'_, T> {
+    fn drop(&mut self) {
+        self.0.held.store(false, Ordering::Release);
+    }
+}
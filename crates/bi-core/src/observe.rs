@@ -0,0 +1,87 @@
+//! Observer Hooks: the engine as an event source for embedders.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::engine::{Mind, Source};
+use crate::lock::Lock;
+use crate::symbols::FlagId;
+
+type Callback = Box<dyn FnMut(FlagId, &Source) + Send>;
+
+/// Registered `on_activate` callbacks. A cloned mind starts without any:
+/// effects belong to the mind that was wired up, not to its copies.
+#[derive(Default)]
+pub(crate) struct Observers(Lock<Vec<Callback>>); // Lock keeps `Mind: Sync`
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = self.0.lock().len();
+        write!(f, "Observers({})", count)
+    }
+}
+
+impl<T> Mind<T> {
+    /// Call `f` for every flag a `tick` newly derives, with the flag and its
+    /// source, in commit order. Callbacks run in registration order once
+    /// the tick has committed everything.
+    ///
+    /// A callback cannot reach the mind. To react by injecting, queue the
+    /// flags (e.g. through a channel) and inject them after the tick: they
+    /// take part from the next tick on. Silent derivations made by `retract`
+    /// are not reported.
+    pub fn on_activate<F: FnMut(FlagId, &Source) + Send + 'static>(&mut self, f: F) {
+        if let Some(callbacks) = self.observers.0.get_mut() {
+            callbacks.push(Box::new(f));
+        }
+    }
+
+    /// Runs every callback on the flags `committed` this tick, in order.
+    pub(crate) fn notify(&mut self, committed: &[FlagId]) {
+        let Some(callbacks) = self.observers.0.get_mut() else {
+            return; // A callback panicked earlier; stay quiet
+        };
+        for &id in committed {
+            if let Some(source) = self.active_memory.get(&id) {
+                for callback in callbacks.iter_mut() {
+                    callback(id, source);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::report::NullReporter;
+
+    #[test]
+    fn callbacks_hear_each_new_flag_in_commit_order() {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind.learn(&["Smoke"], "Alarm");
+        mind.learn(&["Alarm"], "Siren");
+        let (journal, entries) = mpsc::channel();
+        mind.on_activate(move |id, source| {
+            let _ = journal.send((id, source.causes().len()));
+        });
+
+        mind.inject(&["Smoke"]);
+        mind.ponder();
+        let heard: Vec<(String, usize)> = entries
+            .try_iter()
+            .map(|(id, causes)| (mind.label(id), causes))
+            .collect();
+        assert_eq!(heard, [("Alarm".to_string(), 1), ("Siren".to_string(), 1)]);
+    }
+}
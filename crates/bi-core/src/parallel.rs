@@ -0,0 +1,51 @@
+//! Parallel Ticks: rule evaluation split across threads (`parallel` feature).
+
+use std::thread;
+
+use crate::engine::{Fired, Mind};
+use crate::flagset::FlagSet;
+
+// Below this many candidate rules per thread, spawning costs more than it saves.
+const MIN_RULES_PER_THREAD: usize = 4096;
+
+impl<T: Sync> Mind<T> {
+    /// `tick`, evaluating the candidate rules of each priority level on
+    /// several threads (read-only over memory). Results are committed on
+    /// this thread in declaration order, so the log, the sources and
+    /// every later tick are identical to the sequential `tick`.
+    pub fn tick_parallel(&mut self, tick_count: usize) -> bool {
+        self.tick_using(tick_count, Self::evaluate_parallel)
+    }
+
+    /// `ponder` on top of `tick_parallel`.
+    pub fn ponder_parallel(&mut self) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("ponder_parallel").entered();
+        let mut tick = 1;
+        while self.tick_parallel(tick) {
+            tick += 1;
+        }
+        self.report(|r| r.settled());
+    }
+
+    fn evaluate_parallel(&self, level: &[usize], reached: &FlagSet) -> Vec<Fired> {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let threads = threads.min(level.len() / MIN_RULES_PER_THREAD);
+        if threads <= 1 {
+            return self.evaluate(level, reached);
+        }
+
+        let chunk = level.len().div_ceil(threads);
+        thread::scope(|scope| {
+            let workers: Vec<_> = level
+                .chunks(chunk)
+                .map(|part| scope.spawn(move || self.evaluate(part, reached)))
+                .collect();
+            // Joined in chunk order: the concatenation keeps declaration order.
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("rule evaluation panicked"))
+                .collect()
+        })
+    }
+}
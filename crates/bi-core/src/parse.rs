@@ -0,0 +1,258 @@
+//! A line-oriented DSL for authoring rules from text.
+//!
+//! ```text
+//! # Comments run to the end of the line.
+//! Sharp, Solid, Cut -> Separation
+//! SwitchOn - PowerOutage -> LightOn      # `-` inhibits
+//! Separation, Fruit -> "Fruit Slices"    # quotes allow spaces
+//! kitchen::Ready, safety::Ready -> Serve # namespaced labels
+//! ```
+//!
+//! Each line is `triggers (- forbids)* -> output`, where `triggers` and
+//! `forbids` are comma-separated labels. Bare labels are made of letters,
+//! digits and `_`, in `::`-separated segments; anything else must be quoted.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::engine::Mind;
+
+/// Where and why `load_rules` stopped. Lines and columns start at 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Label(String),
+    Comma,
+    Minus,
+    Arrow,
+}
+
+impl<T> Mind<T> {
+    /// Parses `src` and learns every rule in it, in order.
+    /// Returns how many rules were loaded. On error nothing is learned.
+    pub fn load_rules(&mut self, src: &str) -> Result<usize, ParseError> {
+        let mut parsed = Vec::new();
+        for (number, line) in src.lines().enumerate() {
+            let tokens = tokenize(line, number + 1)?;
+            if !tokens.is_empty() {
+                parsed.push(parse_rule(&tokens, line, number + 1)?);
+            }
+        }
+
+        let count = parsed.len();
+        for (triggers, forbids, output) in parsed {
+            let triggers: Vec<&str> = triggers.iter().map(String::as_str).collect();
+            let forbids: Vec<&str> = forbids.iter().map(String::as_str).collect();
+            self.rule(&triggers, &forbids, &output);
+        }
+        Ok(count)
+    }
+
+    /// `load_rules` for a rule module: every unqualified label is read as
+    /// `namespace::label`, so two modules can both say `Ready` without
+    /// meaning the same flag. Qualified labels (`safety::Ready`) are taken
+    /// as written; that is how a module refers to a shared flag.
+    pub fn load_rules_in(&mut self, namespace: &str, src: &str) -> Result<usize, ParseError> {
+        let mut scoped = Mind::new();
+        let count = scoped.load_rules(src)?;
+        self.merge_in(namespace, &scoped);
+        Ok(count)
+    }
+}
+
+fn error(line: usize, column: usize, message: impl Into<String>) -> ParseError {
+    ParseError {
+        line,
+        column,
+        message: message.into(),
+    }
+}
+
+fn is_label_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Tokens of one line with their columns; comments are dropped.
+fn tokenize(line: &str, number: usize) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        match chars[i] {
+            '#' => break,
+            c if c.is_whitespace() => i += 1,
+            ',' => {
+                tokens.push((Token::Comma, column));
+                i += 1;
+            }
+            '-' if chars.get(i + 1) == Some(&'>') => {
+                tokens.push((Token::Arrow, column));
+                i += 2;
+            }
+            '-' => {
+                tokens.push((Token::Minus, column));
+                i += 1;
+            }
+            '"' => {
+                let Some(len) = chars[i + 1..].iter().position(|&c| c == '"') else {
+                    return Err(error(number, column, "unterminated quoted label"));
+                };
+                let label: String = chars[i + 1..i + 1 + len].iter().collect();
+                if label.trim().is_empty() {
+                    return Err(error(number, column, "empty quoted label"));
+                }
+                tokens.push((Token::Label(label), column));
+                i += len + 2;
+            }
+            c if is_label_char(c) => {
+                let mut len = 0;
+                loop {
+                    len += chars[i + len..]
+                        .iter()
+                        .take_while(|c| is_label_char(**c))
+                        .count();
+                    // `::` continues the label only when a segment follows
+                    let next = &chars[i + len..];
+                    if next.len() > 2 && next[..2] == [':', ':'] && is_label_char(next[2]) {
+                        len += 2;
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push((Token::Label(chars[i..i + len].iter().collect()), column));
+                i += len;
+            }
+            c => return Err(error(number, column, format!("unexpected `{}`", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+/// (triggers, forbids, output) of one non-empty line.
+fn parse_rule(
+    tokens: &[(Token, usize)],
+    line: &str,
+    number: usize,
+) -> Result<(Vec<String>, Vec<String>, String), ParseError> {
+    let end = line.chars().count() + 1;
+    let mut pos = 0;
+
+    // A comma-separated list of at least one label.
+    let list = |pos: &mut usize| -> Result<Vec<String>, ParseError> {
+        let mut labels = Vec::new();
+        loop {
+            match tokens.get(*pos) {
+                Some((Token::Label(label), _)) => labels.push(label.clone()),
+                Some((_, column)) => return Err(error(number, *column, "expected a label")),
+                None => return Err(error(number, end, "expected a label")),
+            }
+            *pos += 1;
+            if !matches!(tokens.get(*pos), Some((Token::Comma, _))) {
+                return Ok(labels);
+            }
+            *pos += 1;
+        }
+    };
+
+    let triggers = list(&mut pos)?;
+    let mut forbids = Vec::new();
+    while matches!(tokens.get(pos), Some((Token::Minus, _))) {
+        pos += 1;
+        forbids.extend(list(&mut pos)?);
+    }
+
+    match tokens.get(pos) {
+        Some((Token::Arrow, _)) => pos += 1,
+        Some((_, column)) => return Err(error(number, *column, "expected `-` or `->`")),
+        None => return Err(error(number, end, "expected `->`")),
+    }
+    let output = match tokens.get(pos) {
+        Some((Token::Label(label), _)) => label.clone(),
+        Some((_, column)) => return Err(error(number, *column, "expected the output label")),
+        None => return Err(error(number, end, "expected the output label")),
+    };
+    if let Some((_, column)) = tokens.get(pos + 1) {
+        return Err(error(number, *column, "expected the end of the rule"));
+    }
+    Ok((triggers, forbids, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    fn load_error(src: &str) -> (usize, usize) {
+        let err = quiet().load_rules(src).unwrap_err();
+        (err.line, err.column)
+    }
+
+    #[test]
+    fn minus_inhibits() {
+        let mut mind = quiet();
+        let src = "# lights\n\nSwitchOn - PowerOutage, Fuse -> LightOn\n";
+        assert_eq!(mind.load_rules(src), Ok(1));
+        let rule = &mind.rules[0];
+        assert_eq!(rule.triggers, [mind.symbols.get("SwitchOn").unwrap()]);
+        assert_eq!(rule.forbids.len(), 2);
+
+        mind.inject(&["SwitchOn", "Fuse"]);
+        mind.ponder();
+        assert!(!mind.is_active("LightOn"));
+        mind.retract(&["Fuse"]);
+        assert!(mind.is_active("LightOn"));
+    }
+
+    #[test]
+    fn quoted_and_namespaced_labels() {
+        let mut mind = quiet();
+        let src = "Separation, Fruit -> \"Fruit Slices\"  # quoted\n\
+                   kitchen::Ready, safety::Ready -> Serve\n";
+        assert_eq!(mind.load_rules(src), Ok(2));
+        mind.inject(&["Separation", "Fruit", "kitchen::Ready", "safety::Ready"]);
+        mind.ponder();
+        assert!(mind.is_active("Fruit Slices"));
+        assert!(mind.is_active("Serve"));
+        assert!(!mind.is_active("Ready"));
+    }
+
+    #[test]
+    fn errors_point_at_line_and_column() {
+        assert_eq!(load_error("A -> B\nA, \"Open -> C"), (2, 4));
+        assert_eq!(load_error("A, B C"), (1, 6));
+        assert_eq!(load_error("A, B"), (1, 5));
+        assert_eq!(load_error("A -> B C"), (1, 8));
+        assert_eq!(load_error("A -> B\n\n  A -> B -> C"), (3, 10));
+        assert_eq!(load_error("A ! B -> C"), (1, 3));
+    }
+
+    #[test]
+    fn nothing_is_learned_on_error() {
+        let mut mind = quiet();
+        assert!(mind.load_rules("A -> B\nC ->").is_err());
+        assert!(mind.rules.is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+//! Flag Payloads: data riding on activations (`Mind<T>`).
+//!
+//! The logic stays boolean: a payload never decides whether a rule fires.
+//! It is what an activation carries along, e.g. the value a sensor
+//! measured or the time a fact was seen. Snapshots carry flags, not payloads.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::collections::HashMap;
+use crate::engine::Mind;
+use crate::symbols::FlagId;
+
+type Combine<T> = Arc<dyn Fn(&[&T]) -> T + Send + Sync>;
+
+/// The payloads of a mind and how derived flags get theirs.
+pub(crate) struct Payloads<T> {
+    data: HashMap<FlagId, T>, // Entries of inactive flags are stale
+    combine: Option<Combine<T>>,
+}
+
+impl<T> Default for Payloads<T> {
+    fn default() -> Self {
+        Self {
+            data: HashMap::new(),
+            combine: None,
+        }
+    }
+}
+
+impl<T: Clone> Clone for Payloads<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            combine: self.combine.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Payloads<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Payloads")
+            .field("data", &self.data)
+            .field("combine", &self.combine.is_some())
+            .finish()
+    }
+}
+
+impl<T> Mind<T> {
+    /// `inject` one flag carrying `data`.
+    pub fn inject_with(&mut self, label: &str, data: T) {
+        self.inject_one(label, 1.0);
+        self.report(|r| r.input(label, None));
+        let id = self.id(label);
+        self.payloads.data.insert(id, data);
+    }
+
+    /// The payload of an active flag; `None` if the flag is inactive or
+    /// carries nothing (plain `inject`, or derived without a combiner).
+    pub fn data_of(&self, label: &str) -> Option<&T> {
+        let id = self.symbols.get(label)?;
+        if !self.active_memory.contains_key(&id) {
+            return None;
+        }
+        self.payloads.data.get(&id)
+    }
+
+    /// A derived flag gets `combine` of the payloads its causes carry
+    /// (causes without one are left out). Without a combiner, derived
+    /// flags carry nothing.
+    pub fn combine_payloads(&mut self, combine: impl Fn(&[&T]) -> T + Send + Sync + 'static) {
+        self.payloads.combine = Some(Arc::new(combine));
+    }
+
+    /// Every derived flag carries `T::default()`.
+    pub fn default_payloads(&mut self)
+    where
+        T: Default,
+    {
+        self.combine_payloads(|_| T::default());
+    }
+
+    /// Drops the payload of `id`, which is (re)activated without data.
+    pub(crate) fn clear_payload(&mut self, id: FlagId) {
+        self.payloads.data.remove(&id);
+    }
+
+    /// Gives a newly derived `id` the combined payload of `causes`.
+    pub(crate) fn derive_payload(&mut self, id: FlagId, causes: &[FlagId]) {
+        let Some(combine) = &self.payloads.combine else {
+            self.payloads.data.remove(&id);
+            return;
+        };
+        let inputs: Vec<&T> = causes
+            .iter()
+            .filter_map(|cause| self.payloads.data.get(cause))
+            .collect();
+        let data = combine(&inputs);
+        self.payloads.data.insert(id, data);
+    }
+}
@@ -0,0 +1,729 @@
+//! Persistence and interface contracts: plain-text exports of the topology.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::collections::HashMap;
+#[cfg(feature = "serde")]
+use crate::collections::HashSet;
+use crate::engine::Mind;
+use crate::error::MindError;
+use crate::parse::ParseError;
+use crate::rules::{Rule, RuleChangeKind, Vote};
+#[cfg(feature = "serde")]
+use crate::symbols::namespace_of;
+use crate::symbols::{FlagId, qualify};
+use crate::trace::dot_string;
+
+/// A whole knowledge base in label form, stable across runs whatever
+/// order the ids were assigned in. With the `serde` feature it can be
+/// stored as JSON, RON, etc.; omitted fields take their defaults.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RuleSet {
+    pub labels: Vec<String>, // The vocabulary, in id order
+    pub rules: Vec<RuleSpec>,
+    pub votes: Vec<VoteSpec>,
+}
+
+/// The document `flag_schema_json` writes.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct FlagSchema {
+    flags: Vec<FlagEntry>,
+}
+
+/// One flag of a `FlagSchema`.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct FlagEntry {
+    id: FlagId,
+    label: String,
+    namespace: Option<String>,
+    role: &'static str,
+}
+
+/// A `Rule` in label form.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RuleSpec {
+    pub triggers: Vec<String>,
+    pub any_of: Vec<String>,
+    pub quorum: usize,
+    pub exclusive: bool,
+    pub forbids: Vec<String>,
+    pub strict: bool,
+    pub output: String,
+    pub priority: i32,
+    pub min_confidence: f32,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub group: Option<String>,
+    pub weight: f32,
+}
+
+impl Default for RuleSpec {
+    fn default() -> Self {
+        Self {
+            triggers: Vec::new(),
+            any_of: Vec::new(),
+            quorum: 1,
+            exclusive: false,
+            forbids: Vec::new(),
+            strict: false,
+            output: String::new(),
+            priority: 0,
+            min_confidence: 0.0,
+            group: None,
+            weight: 1.0,
+        }
+    }
+}
+
+/// Outcome of `Mind::merge`: rules taken over, and duplicates left out.
+/// Custom rules count as rules.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MergeReport {
+    pub added: usize,
+    pub skipped: usize,
+}
+
+/// A `Vote` in label form: (output, weighted triggers) per candidate.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct VoteSpec {
+    pub candidates: Vec<(String, Vec<(String, f32)>)>,
+}
+
+impl<T> Mind<T> {
+    /// One line per rule: `output <- trigger1,trigger2 | either1,either2 ! forbid1`.
+    /// The `| ...` and `! ...` parts are omitted when a rule has no OR group
+    /// or no forbids; a group needing `k` > 1 members writes `|k`, exclusive
+    /// groups `^` instead of `|`, and strict rules `!!` instead of `!`.
+    /// A rule with a nonzero priority ends in `@priority`, one with a
+    /// `min_confidence` in `~min_confidence`. Votes, rule groups and weights
+    /// are not exported. Labels must not contain `,`, `|`, `^`, `!`, `@`, `~` or `<-`.
+    pub fn to_adjacency(&self) -> String {
+        let join = |ids: &[FlagId]| {
+            ids.iter()
+                .map(|id| self.label(*id))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let mut out = String::new();
+        for rule in &self.rules {
+            out.push_str(&format!(
+                "{} <- {}",
+                self.label(rule.output),
+                join(&rule.triggers)
+            ));
+            if !rule.any_of.is_empty() {
+                let marker = match (rule.exclusive, rule.quorum) {
+                    (true, _) => "^".to_string(),
+                    (false, 1) => "|".to_string(),
+                    (false, k) => format!("|{}", k),
+                };
+                out.push_str(&format!(" {} {}", marker, join(&rule.any_of)));
+            }
+            if !rule.forbids.is_empty() {
+                let marker = if rule.strict { "!!" } else { "!" };
+                out.push_str(&format!(" {} {}", marker, join(&rule.forbids)));
+            }
+            if rule.priority != 0 {
+                out.push_str(&format!(" @{}", rule.priority));
+            }
+            if rule.min_confidence > 0.0 {
+                out.push_str(&format!(" ~{}", rule.min_confidence));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl Mind {
+    /// Parses the output of `to_adjacency` back into a fresh mind.
+    /// Blank lines are skipped; the error points at the first malformed
+    /// part of the first malformed line.
+    pub fn from_adjacency(src: &str) -> Result<Self, ParseError> {
+        let split = |list: &str| -> Vec<String> {
+            list.split(',')
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        let mut mind = Self::new();
+        for (number, line) in src.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // `part` is a slice of `line`; the column is where it starts.
+            let error = |part: &str, message: String| ParseError {
+                line: number + 1,
+                column: line[..part.as_ptr() as usize - line.as_ptr() as usize]
+                    .chars()
+                    .count()
+                    + 1,
+                message,
+            };
+            let Some((output, rest)) = line.split_once("<-") else {
+                return Err(error(&line[line.len()..], "missing `<-`".to_string()));
+            };
+            if output.trim().is_empty() {
+                return Err(error(line, "missing output before `<-`".to_string()));
+            }
+            let output = output.trim();
+            let (rest, min_confidence) = match rest.rsplit_once('~') {
+                Some((rest, min)) => match min.trim().parse::<f32>() {
+                    Ok(min) if (0.0..=1.0).contains(&min) => (rest, min),
+                    _ => {
+                        let min = min.trim();
+                        return Err(error(min, format!("bad confidence `{}`", min)));
+                    }
+                },
+                None => (rest, 0.0),
+            };
+            let (rest, priority) = match rest.rsplit_once('@') {
+                Some((rest, level)) => match level.trim().parse::<i32>() {
+                    Ok(priority) => (rest, priority),
+                    Err(_) => {
+                        let level = level.trim();
+                        return Err(error(level, format!("bad priority `{}`", level)));
+                    }
+                },
+                None => (rest, 0),
+            };
+            let (triggers, forbids, strict) = match rest.split_once("!!") {
+                Some((triggers, forbids)) => (triggers, forbids, true),
+                None => match rest.split_once('!') {
+                    Some((triggers, forbids)) => (triggers, forbids, false),
+                    None => (rest, "", false),
+                },
+            };
+
+            let (triggers, any_of, exclusive) = match triggers.split_once('^') {
+                Some((triggers, any_of)) => (triggers, any_of, true),
+                None => match triggers.split_once('|') {
+                    Some((triggers, any_of)) => (triggers, any_of, false),
+                    None => (triggers, "", false),
+                },
+            };
+            let digits = any_of.len()
+                - any_of
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .len();
+            let group = any_of.trim_start();
+            let (quorum, any_of) = match any_of[..digits].parse::<usize>() {
+                Ok(k) => (k, &any_of[digits..]),
+                Err(_) => (1, any_of),
+            };
+
+            let triggers = split(triggers);
+            let any_of = split(any_of);
+            let forbids = split(forbids);
+            let triggers: Vec<&str> = triggers.iter().map(String::as_str).collect();
+            let any_of: Vec<&str> = any_of.iter().map(String::as_str).collect();
+            if quorum == 0 || (!any_of.is_empty() && quorum > any_of.len()) {
+                return Err(error(
+                    group,
+                    format!(
+                        "threshold {} does not fit {} input(s)",
+                        quorum,
+                        any_of.len()
+                    ),
+                ));
+            }
+            let forbids: Vec<&str> = forbids.iter().map(String::as_str).collect();
+            mind.rule_or(&triggers, &any_of, &forbids, output);
+            if let Some(rule) = mind.rules.last_mut() {
+                rule.strict = strict;
+                rule.quorum = quorum;
+                rule.exclusive = exclusive;
+                rule.priority = priority;
+                rule.min_confidence = min_confidence;
+            }
+        }
+        Ok(mind)
+    }
+}
+
+impl<T> Mind<T> {
+    /// Every label, rule and vote of this mind, in label form.
+    pub fn export_rules(&self) -> RuleSet {
+        let labels = |ids: &[FlagId]| ids.iter().map(|id| self.label(*id)).collect();
+        RuleSet {
+            labels: self
+                .symbols
+                .ids()
+                .into_iter()
+                .map(|id| self.label(id))
+                .collect(),
+            rules: self
+                .rules
+                .iter()
+                .map(|rule| RuleSpec {
+                    triggers: labels(&rule.triggers),
+                    any_of: labels(&rule.any_of),
+                    quorum: rule.quorum,
+                    exclusive: rule.exclusive,
+                    forbids: labels(&rule.forbids),
+                    strict: rule.strict,
+                    output: self.label(rule.output),
+                    priority: rule.priority,
+                    min_confidence: rule.min_confidence,
+                    group: rule.group.map(|group| self.group_names[group].clone()),
+                    weight: rule.weight,
+                })
+                .collect(),
+            votes: self
+                .votes
+                .iter()
+                .map(|vote| VoteSpec {
+                    candidates: vote
+                        .candidates
+                        .iter()
+                        .map(|(out, weighted)| {
+                            let weighted =
+                                weighted.iter().map(|(t, w)| (self.label(*t), *w)).collect();
+                            (self.label(*out), weighted)
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Adds the labels, rules and votes of `set` to this mind, after the
+    /// ones it already has. Imported into a fresh mind, an exported set
+    /// reproduces the same ids and the same inference.
+    ///
+    /// # Errors
+    /// If a rule has a group quorum outside `1..=any_of.len()`; nothing is imported.
+    pub fn import_rules(&mut self, set: RuleSet) -> Result<(), MindError> {
+        let bad_quorum = set.rules.iter().find(|spec| {
+            !spec.any_of.is_empty() && !(1..=spec.any_of.len()).contains(&spec.quorum)
+        });
+        if let Some(spec) = bad_quorum {
+            return Err(MindError::InvalidThreshold {
+                output: spec.output.clone(),
+                k: spec.quorum,
+                inputs: spec.any_of.len(),
+            });
+        }
+
+        for label in &set.labels {
+            self.id(label);
+        }
+        for spec in set.rules {
+            let ids = |mind: &mut Self, labels: &[String]| -> Vec<FlagId> {
+                labels.iter().map(|l| mind.id(l)).collect()
+            };
+            let rule = Rule {
+                triggers: ids(self, &spec.triggers),
+                any_of: ids(self, &spec.any_of),
+                quorum: spec.quorum,
+                exclusive: spec.exclusive,
+                forbids: ids(self, &spec.forbids),
+                strict: spec.strict,
+                output: self.id(&spec.output),
+                priority: spec.priority,
+                min_confidence: spec.min_confidence,
+                group: spec.group.as_deref().map(|group| self.group_id(group)),
+                weight: spec.weight,
+                added_at: 0,
+                modified_at: 0,
+            };
+            self.rules.push(rule);
+            self.note_rule_edit(&[self.rules.len() - 1], RuleChangeKind::Added);
+        }
+        for spec in set.votes {
+            let candidates = spec
+                .candidates
+                .iter()
+                .map(|(out, weighted)| {
+                    let weighted = weighted.iter().map(|(t, w)| (self.id(t), *w)).collect();
+                    (self.id(out), weighted)
+                })
+                .collect();
+            self.votes.push(Vote { candidates });
+        }
+        self.reindex();
+        Ok(())
+    }
+
+    /// Adds the static knowledge of `other` (rules, custom rules, votes and
+    /// exclusive pairs) to this mind. Labels are resolved through this mind's
+    /// table: identical labels unify, new ones get fresh ids. Rules and votes
+    /// that this mind already has are skipped, as are custom rules sharing
+    /// its gate (e.g. merged twice). Dynamic memory is not merged.
+    pub fn merge(&mut self, other: &Mind) -> MergeReport {
+        self.merge_labeled(other, |label| label.to_string())
+    }
+
+    /// `merge` of a rule module into `namespace`: the unqualified labels of
+    /// `other` become `namespace::label` (see `qualify`), so its `Ready`
+    /// never unifies with another module's `Ready`. Labels `other` already
+    /// qualifies (`safety::Ready`) are the deliberately shared ones and
+    /// unify as in `merge`.
+    pub fn merge_in(&mut self, namespace: &str, other: &Mind) -> MergeReport {
+        self.merge_labeled(other, |label| qualify(namespace, label))
+    }
+
+    /// `merge`, with every label of `other` passed through `rename` first.
+    fn merge_labeled(&mut self, other: &Mind, rename: impl Fn(&str) -> String) -> MergeReport {
+        let label = |id: FlagId| rename(&other.label(id));
+        let ids = |mind: &mut Self, ids: &[FlagId]| -> Vec<FlagId> {
+            ids.iter().map(|id| mind.id(&label(*id))).collect()
+        };
+        for id in other.symbols.ids() {
+            self.id(&label(id));
+        }
+
+        let mut report = MergeReport::default();
+        for rule in &other.rules {
+            let rule = Rule {
+                triggers: ids(self, &rule.triggers),
+                any_of: ids(self, &rule.any_of),
+                forbids: ids(self, &rule.forbids),
+                output: self.id(&label(rule.output)),
+                group: rule
+                    .group
+                    .map(|group| self.group_id(&other.group_names[group])),
+                ..rule.clone()
+            };
+            if self.rules.iter().any(|own| own.same_logic(&rule)) {
+                report.skipped += 1;
+            } else {
+                self.rules.push(rule);
+                self.note_rule_edit(&[self.rules.len() - 1], RuleChangeKind::Added);
+                report.added += 1;
+            }
+        }
+        for custom in &other.custom_rules {
+            let custom =
+                custom.relabeled(ids(self, &custom.inputs), self.id(&label(custom.output)));
+            if self.custom_rules.iter().any(|own| own.same_rule(&custom)) {
+                report.skipped += 1;
+            } else {
+                self.custom_rules.push(custom);
+                report.added += 1;
+            }
+        }
+        for vote in &other.votes {
+            let candidates = vote
+                .candidates
+                .iter()
+                .map(|(out, weighted)| {
+                    let weighted = weighted
+                        .iter()
+                        .map(|(t, w)| (self.id(&label(*t)), *w))
+                        .collect();
+                    (self.id(&label(*out)), weighted)
+                })
+                .collect();
+            let vote = Vote { candidates };
+            if !self.votes.contains(&vote) {
+                self.votes.push(vote);
+            }
+        }
+        for &(a, b) in &other.exclusive_pairs {
+            self.declare_exclusive(&label(a), &label(b));
+        }
+        for &id in &other.declared_inputs {
+            self.declare_inputs(&[&label(id)]);
+        }
+        for &id in &other.declared_outputs {
+            self.declare_outputs(&[&label(id)]);
+        }
+        for (&group, &gate) in &other.group_gates {
+            let group = self.group_id(&other.group_names[group]);
+            let gate = self.id(&label(gate));
+            self.group_gates.insert(group, gate);
+        }
+        self.reindex();
+        report
+    }
+
+    /// Assigns ids to `labels` in sorted order, so that two programs naming
+    /// the same flags get the same ids whatever order they learn rules in.
+    /// Call it on a fresh mind, before anything is learned; labels seen
+    /// later still get fresh ids after these. Duplicates (after the
+    /// normalizer) are interned once.
+    ///
+    /// # Errors
+    /// If this mind already assigned ids; nothing changes.
+    pub fn freeze_labels(&mut self, labels: &[&str]) -> Result<(), MindError> {
+        if !self.symbols.is_empty() {
+            return Err(MindError::LabelsAssigned {
+                count: self.symbols.len(),
+            });
+        }
+        let mut sorted = labels.to_vec();
+        sorted.sort();
+        for label in sorted {
+            self.id(label);
+        }
+        Ok(())
+    }
+
+    /// Every (id, label) of this mind, ascending ids: the mapping to keep
+    /// next to exported `FlagId`-keyed data, for `remap_ids` later.
+    pub fn label_map(&self) -> Vec<(FlagId, String)> {
+        self.symbols
+            .ids()
+            .into_iter()
+            .map(|id| (id, self.label(id)))
+            .collect()
+    }
+
+    /// Migration of `FlagId`-keyed data: the id in this mind of each old id,
+    /// matched by label through `old` (a `label_map` of the mind that
+    /// wrote the data). Old ids whose label this mind does not know are
+    /// left out.
+    pub fn remap_ids(&self, old: &[(FlagId, String)]) -> HashMap<FlagId, FlagId> {
+        old.iter()
+            .filter_map(|(old_id, label)| Some((*old_id, self.symbols.get(label)?)))
+            .collect()
+    }
+
+    /// JSON description of the vocabulary, one entry per flag in id order.
+    /// `role` is `input` (only consumed), `output` (only produced) or `both`;
+    /// `namespace` is that of a qualified label (see `namespace_of`), else null.
+    #[cfg(feature = "serde")]
+    pub fn flag_schema_json(&self) -> String {
+        let mut produced: HashSet<FlagId> = HashSet::new();
+        let mut consumed: HashSet<FlagId> = HashSet::new();
+        for rule in &self.rules {
+            produced.insert(rule.output);
+            consumed.extend(
+                rule.triggers
+                    .iter()
+                    .chain(&rule.any_of)
+                    .chain(&rule.forbids),
+            );
+        }
+        for vote in &self.votes {
+            for (output, triggers) in &vote.candidates {
+                produced.insert(*output);
+                consumed.extend(triggers.iter().map(|(t, _)| t));
+            }
+        }
+
+        let flags = self
+            .symbols
+            .ids()
+            .into_iter()
+            .map(|id| {
+                let label = self.label(id);
+                FlagEntry {
+                    id,
+                    namespace: namespace_of(&label).map(str::to_string),
+                    role: match (produced.contains(&id), consumed.contains(&id)) {
+                        (true, true) => "both",
+                        (true, false) => "output",
+                        (false, _) => "input",
+                    },
+                    label,
+                }
+            })
+            .collect();
+        serde_json::to_string_pretty(&FlagSchema { flags }).unwrap_or_default() // Plain strings and numbers always serialize
+    }
+
+    /// Graphviz DOT of the rule base itself, before anything runs (`to_dot`
+    /// draws active memory instead). Flags no rule produces are boxes, the
+    /// rest ellipses. A plain AND rule points its triggers at the output;
+    /// a rule with a group goes through a diamond gate labeled `OR`,
+    /// `k of n` or `XOR`, its mandatory triggers drawn bold. Forbids are red dashed edges ending in a bar
+    /// (`!!` for strict ones), vote triggers blue edges with their weight.
+    pub fn rules_to_dot(&self) -> String {
+        let produced = self.produced_ids();
+        let mut out = String::from("digraph rules {\n    rankdir=LR;\n");
+        for id in self.symbols.ids() {
+            let shape = if produced.contains(&id) {
+                "ellipse"
+            } else {
+                "box"
+            };
+            out.push_str(&format!(
+                "    f{} [label={}, shape={}];\n",
+                id,
+                dot_string(&self.label(id)),
+                shape
+            ));
+        }
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            let gated = !rule.any_of.is_empty();
+            let target = if gated {
+                let gate = match (rule.exclusive, rule.quorum) {
+                    (true, _) => "XOR".to_string(),
+                    (false, 1) => "OR".to_string(),
+                    (false, k) => format!("{} of {}", k, rule.any_of.len()),
+                };
+                out.push_str(&format!(
+                    "    r{} [label={}, shape=diamond];\n",
+                    index,
+                    dot_string(&gate)
+                ));
+                out.push_str(&format!("    r{} -> f{};\n", index, rule.output));
+                format!("r{}", index)
+            } else {
+                format!("f{}", rule.output)
+            };
+            for trigger in &rule.triggers {
+                let style = if gated { " [style=bold]" } else { "" };
+                out.push_str(&format!("    f{} -> {}{};\n", trigger, target, style));
+            }
+            for member in &rule.any_of {
+                out.push_str(&format!("    f{} -> {};\n", member, target));
+            }
+            for forbid in &rule.forbids {
+                let label = if rule.strict { ", label=\"!!\"" } else { "" };
+                out.push_str(&format!(
+                    "    f{} -> {} [color=red, style=dashed, arrowhead=tee{}];\n",
+                    forbid, target, label
+                ));
+            }
+        }
+
+        for vote in &self.votes {
+            for (output, triggers) in &vote.candidates {
+                for (trigger, weight) in triggers {
+                    out.push_str(&format!(
+                        "    f{} -> f{} [color=blue, label=\"{}\"];\n",
+                        trigger, output, weight
+                    ));
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    #[test]
+    fn adjacency_text_round_trips() {
+        let mut mind = quiet();
+        mind.learn_or(&["Power"], &["Switch", "Timer"], "Lamp");
+        mind.rule_xor(&["Heat", "Cool"], "ModeValid");
+        mind.rule_threshold(&["Smoke", "Heat", "Gas"], 2, "Evacuate")
+            .unwrap();
+        mind.rule_strict(&["Lamp"], &["Daylight"], "Glow");
+        mind.rule_fuzzy(&["Glow"], "Cozy", 0.5);
+        let text = mind.to_adjacency();
+
+        let copy = Mind::from_adjacency(&text).unwrap();
+        assert_eq!(copy.to_adjacency(), text);
+    }
+
+    #[test]
+    fn merged_custom_rules_keep_their_gates() {
+        let mut stairs = quiet();
+        stairs.rule_custom(&["Hall", "Landing"], "StairLight", |up| up[0] != up[1]);
+        stairs.learn(&["StairLight"], "Glow");
+
+        let mut house = quiet();
+        house.inject(&["Door"]); // Other ids than the module's
+        assert_eq!(
+            house.merge_in("stairs", &stairs),
+            MergeReport {
+                added: 2,
+                skipped: 0
+            }
+        );
+        assert_eq!(
+            house.merge_in("stairs", &stairs),
+            MergeReport {
+                added: 0,
+                skipped: 2
+            }
+        );
+        assert_eq!(house.custom_rules.len(), 1);
+
+        house.inject(&["stairs::Landing"]);
+        house.ponder();
+        assert!(house.is_active("stairs::Glow"));
+    }
+
+    #[test]
+    fn adjacency_errors_point_at_the_bad_part() {
+        let at = |src: &str| {
+            let err = Mind::from_adjacency(src).unwrap_err();
+            (err.line, err.column)
+        };
+        assert_eq!(at("Lamp <- Power\nLamp Power"), (2, 11));
+        assert_eq!(at(" <- Power"), (1, 1));
+        assert_eq!(at("Lamp <- Power ~ high"), (1, 17));
+        assert_eq!(at("Lamp <- Power @x ~0.5"), (1, 16));
+        assert_eq!(at("Evacuate <- |3 Smoke,Heat"), (1, 14));
+        assert_eq!(
+            Mind::from_adjacency("Lamp <- Power @x")
+                .unwrap_err()
+                .to_string(),
+            "1:16: bad priority `x`"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn the_flag_schema_lists_roles_and_namespaces() {
+        let mut mind = quiet();
+        mind.learn(&["kitchen::Smoke"], "Alarm");
+        mind.learn(&["Alarm"], "Siren");
+        let schema: serde_json::Value = serde_json::from_str(&mind.flag_schema_json()).unwrap();
+        assert_eq!(
+            schema,
+            serde_json::json!({ "flags": [
+                { "id": 1, "label": "kitchen::Smoke", "namespace": "kitchen", "role": "input" },
+                { "id": 2, "label": "Alarm", "namespace": null, "role": "both" },
+                { "id": 3, "label": "Siren", "namespace": null, "role": "output" },
+            ] })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn a_rule_set_survives_json_into_a_fresh_mind() {
+        let mut mind = quiet();
+        mind.learn_or(&["Power"], &["Switch", "Timer"], "Lamp");
+        mind.rule(&["Lamp"], &["Daylight"], "Glow");
+        mind.rule_fuzzy(&["Glow"], "Cozy", 0.5);
+        mind.learn_in_group("night", &["Glow", "Late"], "Sleepy");
+        mind.learn_vote(&[("Tea", &[("Cozy", 1.0)]), ("Coffee", &[("Late", 0.5)])]);
+        let json = serde_json::to_string(&mind.export_rules()).unwrap();
+
+        let mut copy = quiet();
+        copy.inject(&["Late"]); // Other ids than the original's
+        copy.reset_memory();
+        copy.import_rules(serde_json::from_str(&json).unwrap())
+            .unwrap();
+
+        for target in [&mut mind, &mut copy] {
+            target.inject_weighted(&[("Power", 1.0), ("Timer", 0.8), ("Late", 1.0)]);
+            target.ponder();
+        }
+        assert_eq!(copy.active_labels(), mind.active_labels());
+        assert_eq!(copy.confidence("Cozy"), mind.confidence("Cozy"));
+    }
+}
@@ -0,0 +1,24 @@
+//! The names almost every embedder needs, in one import.
+//!
+//! ```
+//! use bi_core::prelude::*;
+//!
+//! let mut mind = Mind::new();
+//! mind.set_reporter(NullReporter);
+//! mind.learn(&["Knife"], "Sharp");
+//! mind.inject(&["Knife"]);
+//! mind.ponder();
+//! assert!(matches!(mind.source_of("Sharp"), Some(Source::Derived { .. })));
+//! ```
+//!
+//! Only the core vocabulary is here: building a mind, feeding it, reading
+//! its memory and reporting on it. Analyses, persistence and the optional
+//! features keep their types at the crate root (or in their module), so a
+//! glob import never brings in names an embedder did not ask for.
+
+pub use crate::builder::MindBuilder;
+pub use crate::engine::{FlagState, Mind, Source};
+pub use crate::error::MindError;
+pub use crate::flagset::FlagSet;
+pub use crate::report::{NullReporter, Reporter};
+pub use crate::symbols::FlagId;
@@ -0,0 +1,101 @@
+//! Goal-Directed Queries: backward chaining from one goal.
+//!
+//! `ponder` derives everything derivable. `prove` only visits the rules
+//! that can lead to the goal, and leaves memory untouched.
+
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+use crate::engine::Mind;
+use crate::flagset::FlagSet;
+use crate::rules::Rule;
+use crate::symbols::FlagId;
+
+impl<T> Mind<T> {
+    /// Whether `ponder` could derive `goal` from the flags active now, found
+    /// by chaining backwards through the rules that output it. Returns the
+    /// active flags the proof rests on (ascending ids), or `None`.
+    ///
+    /// A forbid blocks a rule if it is active or itself provable; a strict
+    /// rule needs its forbids asserted false. A flag the search is already
+    /// trying to prove counts as unprovable, so cyclic rules end. Like the
+    /// static analyses, `prove` is crisp (confidences are ignored) and
+    /// ignores votes; rules of disabled groups are left out, and a group's
+    /// gate must be provable like a trigger.
+    pub fn prove(&self, goal: &str) -> Option<Vec<FlagId>> {
+        let goal = self.symbols.get(goal)?;
+        let mut proven = HashMap::new();
+        let support = self.prove_id(goal, &mut Vec::new(), &mut proven)?;
+        Some(support.into_iter().collect())
+    }
+
+    /// The support of `id`; `stack` holds the goals being proven.
+    /// Only successes are cached: a failure may be owed to the stack.
+    fn prove_id(
+        &self,
+        id: FlagId,
+        stack: &mut Vec<FlagId>,
+        proven: &mut HashMap<FlagId, FlagSet>,
+    ) -> Option<FlagSet> {
+        if self.active_memory.contains_key(&id) {
+            return Some(FlagSet::from([id]));
+        }
+        if let Some(support) = proven.get(&id) {
+            return Some(support.clone());
+        }
+        if stack.contains(&id) {
+            return None;
+        }
+
+        stack.push(id);
+        let support = (0..self.rules.len())
+            .filter(|&index| self.rules[index].output == id && self.switched_on(&self.rules[index]))
+            .find_map(|index| self.prove_rule(&self.rules[index], stack, proven));
+        stack.pop();
+        if let Some(support) = &support {
+            proven.insert(id, support.clone());
+        }
+        support
+    }
+
+    fn prove_rule(
+        &self,
+        rule: &Rule,
+        stack: &mut Vec<FlagId>,
+        proven: &mut HashMap<FlagId, FlagSet>,
+    ) -> Option<FlagSet> {
+        let mut support = FlagSet::new();
+        for &trigger in rule.triggers.iter().chain(&self.gate(rule)) {
+            support = support.union(&self.prove_id(trigger, stack, proven)?);
+        }
+        if !rule.any_of.is_empty() {
+            let members: Vec<FlagSet> = rule
+                .any_of
+                .iter()
+                .filter_map(|&member| self.prove_id(member, stack, proven))
+                .collect();
+            let fits = if rule.exclusive {
+                members.len() == 1
+            } else {
+                members.len() >= rule.quorum
+            };
+            if !fits {
+                return None;
+            }
+            for member in members.iter().take(rule.quorum) {
+                support = support.union(member);
+            }
+        }
+        for &forbid in &rule.forbids {
+            let blocked = if rule.strict {
+                !self.known_false.contains(&forbid)
+            } else {
+                self.prove_id(forbid, stack, proven).is_some()
+            };
+            if blocked {
+                return None;
+            }
+        }
+        Some(support)
+    }
+}
@@ -0,0 +1,266 @@
+//! Replaying a recorded session: a JSONL derivation trace, checked
+//! against the rules, rebuilds the active memory without pondering.
+//!
+//! Each line is one flag of `trace_json_all`, causes before the flags
+//! reasoned from them: `{ "label": ..., "kind": "input" | "derived" | "missing",
+//! "causes": [label, ...], "confidence": ... }`. `confidence` may be left out
+//! (full strength); `missing` lines are skipped.
+
+use core::fmt;
+use std::io::BufRead;
+
+use serde_json::{Value, json};
+
+use crate::engine::Mind;
+use crate::error::MindError;
+use crate::flagset::FlagSet;
+use crate::persist::RuleSet;
+use crate::symbols::FlagId;
+
+/// Why `Mind::from_jsonl_trace` could not rebuild a session. Lines are
+/// counted from 1.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplayError {
+    /// The rules did not import (see `import_rules`).
+    Rules(MindError),
+    /// Reading the trace failed.
+    Io { line: usize, message: String },
+    /// The line is not a trace entry.
+    Format { line: usize, message: String },
+    /// No rule, vote or custom rule derives `label` from the recorded
+    /// causes, or one of them was not active yet.
+    Unjustified { line: usize, label: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Rules(err) => write!(f, "{}", err),
+            ReplayError::Io { line, message } | ReplayError::Format { line, message } => {
+                write!(f, "line {}: {}", line, message)
+            }
+            ReplayError::Unjustified { line, label } => write!(
+                f,
+                "line {}: `{}` does not follow from its recorded causes",
+                line, label
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ReplayError {}
+
+impl Mind {
+    /// A mind with `rules` whose memory is the session recorded in `trace`
+    /// (see `trace_jsonl`), replayed line by line. Inputs are injected as
+    /// recorded; a derived flag is committed only if its causes are already
+    /// active and an enabled rule, a vote candidate or a custom rule
+    /// derives it from exactly them.
+    pub fn from_jsonl_trace<R: BufRead>(rules: RuleSet, trace: R) -> Result<Mind, ReplayError> {
+        let mut mind = Mind::new();
+        mind.import_rules(rules).map_err(ReplayError::Rules)?;
+        for (index, text) in trace.lines().enumerate() {
+            let line = index + 1;
+            let text = text.map_err(|err| ReplayError::Io {
+                line,
+                message: err.to_string(),
+            })?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let entry =
+                TraceLine::parse(&text).map_err(|message| ReplayError::Format { line, message })?;
+            match entry.kind.as_str() {
+                "input" => mind.inject_one(&entry.label, entry.confidence),
+                "derived" => {
+                    let id = mind.id(&entry.label);
+                    let causes: Vec<FlagId> =
+                        entry.causes.iter().map(|label| mind.id(label)).collect();
+                    if !mind.justifies(id, &causes) {
+                        return Err(ReplayError::Unjustified {
+                            line,
+                            label: entry.label,
+                        });
+                    }
+                    mind.commit(mind.ticks_seen, id, causes, entry.confidence);
+                }
+                "missing" => {}
+                kind => {
+                    return Err(ReplayError::Format {
+                        line,
+                        message: format!("unknown kind `{}`", kind),
+                    });
+                }
+            }
+        }
+        Ok(mind)
+    }
+}
+
+impl<T> Mind<T> {
+    /// `trace_json_all` for `targets` as JSON Lines, one flag per line,
+    /// every cause before the flags reasoned from it, so that
+    /// `from_jsonl_trace` can replay it. Each line also records the
+    /// flag's confidence.
+    pub fn trace_jsonl(&self, targets: &[&str]) -> String {
+        let forest = self.trace_json_all(targets);
+        let mut text = String::new();
+        let mut seen = Vec::new();
+        for target in targets {
+            self.write_jsonl(&forest["nodes"], target, &mut seen, &mut text);
+        }
+        text
+    }
+
+    /// Appends `label` to `text` after its causes (post-order); `seen`
+    /// holds the labels already written or on the way.
+    fn write_jsonl(&self, nodes: &Value, label: &str, seen: &mut Vec<String>, text: &mut String) {
+        if seen.iter().any(|done| done == label) {
+            return;
+        }
+        seen.push(label.to_string());
+        let node = &nodes[label];
+        for cause in node["causes"].as_array().into_iter().flatten() {
+            if let Some(cause) = cause.as_str() {
+                self.write_jsonl(nodes, cause, seen, text);
+            }
+        }
+        let mut line = json!({ "label": label, "kind": node["kind"], "causes": node["causes"] });
+        if let Some(confidence) = self.confidence(label) {
+            line["confidence"] = json!(confidence);
+        }
+        text.push_str(&line.to_string());
+        text.push('\n');
+    }
+
+    /// Whether `causes`, all active, derive `id` through an enabled rule
+    /// (with every trigger and the group members among them), a vote
+    /// candidate (a subset of its triggers) or a custom rule.
+    fn justifies(&self, id: FlagId, causes: &[FlagId]) -> bool {
+        if !causes
+            .iter()
+            .all(|cause| self.active_memory.contains_key(cause))
+        {
+            return false;
+        }
+        let active = self.active_ids();
+        let given: FlagSet = causes.iter().copied().collect();
+        let by_rule = self.rules.iter().enumerate().any(|(index, rule)| {
+            rule.output == id
+                && self.rule_enabled(index, &active)
+                && rule
+                    .triggers
+                    .iter()
+                    .chain(&rule.any_of)
+                    .filter(|t| given.contains(t))
+                    .count()
+                    == causes.len()
+                && self.triggered(rule, &given)
+        });
+        let by_vote = self.votes.iter().any(|vote| {
+            vote.candidates.iter().any(|(out, triggers)| {
+                *out == id && causes.iter().all(|c| triggers.iter().any(|(t, _)| t == c))
+            })
+        });
+        by_rule || by_vote || self.custom_derivation(id, causes)
+    }
+}
+
+/// One parsed line of a JSONL trace.
+struct TraceLine {
+    label: String,
+    kind: String,
+    causes: Vec<String>,
+    confidence: f32,
+}
+
+impl TraceLine {
+    fn parse(text: &str) -> Result<TraceLine, String> {
+        let value: Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        let field = |name: &str| {
+            value[name]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| format!("missing string `{}`", name))
+        };
+        let causes = match &value["causes"] {
+            Value::Null => Vec::new(),
+            Value::Array(causes) => causes
+                .iter()
+                .map(|cause| cause.as_str().map(str::to_string))
+                .collect::<Option<_>>()
+                .ok_or("`causes` must hold labels")?,
+            _ => return Err("`causes` must be a list".to_string()),
+        };
+        let confidence = match &value["confidence"] {
+            Value::Null => 1.0,
+            other => other.as_f64().ok_or("`confidence` must be a number")? as f32,
+        };
+        Ok(TraceLine {
+            label: field("label")?,
+            kind: field("kind")?,
+            causes,
+            confidence,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    fn kitchen() -> Mind {
+        let mut mind = quiet();
+        mind.learn(&["Stove", "Pot"], "Hot");
+        mind.rule_fuzzy(&["Hot"], "Boiling", 0.5);
+        mind.learn_inhibited(&["Boiling"], &["Lid"], "Steam");
+        mind.inject_weighted(&[("Stove", 0.8), ("Pot", 1.0)]);
+        mind.ponder();
+        mind
+    }
+
+    #[test]
+    fn replayed_trace_rebuilds_the_session() {
+        let mind = kitchen();
+        let labels = mind.active_labels();
+        let targets: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let trace = mind.trace_jsonl(&targets);
+
+        let replayed = Mind::from_jsonl_trace(mind.export_rules(), trace.as_bytes()).unwrap();
+        assert_eq!(replayed.active_labels(), labels);
+        assert_eq!(
+            replayed.trace_json_all(&targets),
+            mind.trace_json_all(&targets)
+        );
+        assert_eq!(replayed.confidence("Steam"), mind.confidence("Steam"));
+    }
+
+    #[test]
+    fn unjustified_derivation_is_rejected() {
+        let mind = kitchen();
+        let trace = concat!(
+            r#"{"label":"Stove","kind":"input","causes":[]}"#,
+            "\n",
+            r#"{"label":"Hot","kind":"derived","causes":["Stove"]}"#,
+            "\n",
+        );
+        let err = Mind::from_jsonl_trace(mind.export_rules(), trace.as_bytes()).unwrap_err();
+        assert_eq!(
+            err,
+            ReplayError::Unjustified {
+                line: 2,
+                label: "Hot".into()
+            }
+        );
+
+        let garbled = Mind::from_jsonl_trace(mind.export_rules(), "{".as_bytes());
+        assert!(matches!(garbled, Err(ReplayError::Format { line: 1, .. })));
+    }
+}
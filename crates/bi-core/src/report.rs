@@ -0,0 +1,293 @@
+//! Output Sinks: where a `Mind` reports what it is doing.
+//!
+//! The engine never prints progress itself; it hands every event to its
+//! `Reporter`. `ConsoleReporter` (the default, `std` feature) writes the colored
+//! log, `NullReporter` keeps a library mind silent, `TracingReporter`
+//! (`tracing` feature) emits structured events, and embedders implement
+//! the trait to route events into a buffer, a logger or a test harness.
+
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use console::style;
+
+use crate::engine::Mind;
+use crate::lock::Lock;
+
+/// Receives the progress events of a `Mind`, in the order they happen.
+/// Every method defaults to doing nothing, so a sink only implements the
+/// events it cares about.
+pub trait Reporter: Send {
+    /// `inject` (no confidence) or `inject_weighted`.
+    fn input(&mut self, _label: &str, _confidence: Option<f32>) {}
+    /// `inject_batch`; by default one `input` per label.
+    fn inputs(&mut self, labels: &[&str]) {
+        for label in labels {
+            self.input(label, None);
+        }
+    }
+    /// `inject_with_ttl` with a finite lifetime.
+    fn input_with_ttl(&mut self, _label: &str, _ttl: usize) {}
+    /// `inject_false`; `refused` if the flag was active and stays true.
+    fn asserted_false(&mut self, _label: &str, _refused: bool) {}
+    /// `retract` withdrawing an input.
+    fn withdrawn(&mut self, _label: &str) {}
+    /// A tick begins; derivations and votes of tick `tick` follow.
+    fn tick_started(&mut self, _tick: usize) {}
+    /// A rule derived `output` from `causes` with fuzzy `value` (1.0 if crisp).
+    fn derived(&mut self, _tick: usize, _causes: &[String], _output: &str, _value: f32) {}
+    /// A vote was decided; `scores` holds every candidate in declaration order.
+    fn vote(&mut self, _tick: usize, _scores: &[(String, f32)]) {}
+    /// Truth maintenance removed a conclusion that lost its support.
+    fn lost(&mut self, _label: &str) {}
+    /// `run_clock` reached a time with scheduled events.
+    fn clock(&mut self, _time: usize) {}
+    /// `tick_with_decay` let inputs expire at `age`.
+    fn decay(&mut self, _age: usize) {}
+    /// `ponder`, `feed` or `retract` finished: memory is stable again.
+    fn settled(&mut self) {}
+}
+
+/// How `ConsoleReporter` lays out its log.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LogLayout {
+    /// Later derivations of a tick are indented under the first `[Tick n]`
+    /// header instead of repeating it.
+    pub align_ticks: bool,
+    /// `ponder` and `retract` end with an empty line.
+    pub spacer: bool,
+}
+
+impl Default for LogLayout {
+    fn default() -> Self {
+        Self {
+            align_ticks: true,
+            spacer: false,
+        }
+    }
+}
+
+/// The colored stdout log. `console` drops the colors when stdout is no TTY.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct ConsoleReporter {
+    layout: LogLayout,
+    logged: usize, // Derivations and votes so far this tick
+}
+
+#[cfg(feature = "std")]
+impl ConsoleReporter {
+    pub fn new(layout: LogLayout) -> Self {
+        Self { layout, logged: 0 }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Reporter for ConsoleReporter {
+    fn input(&mut self, label: &str, confidence: Option<f32>) {
+        match confidence {
+            Some(confidence) => {
+                println!("[Input] + `{}` ({:.2})", style(label).green(), confidence)
+            }
+            None => println!("[Input] + `{}`", style(label).green()),
+        }
+    }
+
+    fn inputs(&mut self, labels: &[&str]) {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|label| format!("`{}`", style(label).green()))
+            .collect();
+        println!("[Input] + {}", labels.join(", "));
+    }
+
+    fn input_with_ttl(&mut self, label: &str, ttl: usize) {
+        println!("[Input] + `{}` (ttl {})", style(label).green(), ttl);
+    }
+
+    fn asserted_false(&mut self, label: &str, refused: bool) {
+        if refused {
+            println!("[Input] ! `{}` refused: already active", style(label).red());
+        } else {
+            println!("[Input] ! `{}`", style(label).cyan());
+        }
+    }
+
+    fn withdrawn(&mut self, label: &str) {
+        println!("[Input] - `{}`", style(label).red());
+    }
+
+    fn tick_started(&mut self, _tick: usize) {
+        self.logged = 0;
+    }
+
+    fn derived(&mut self, tick: usize, causes: &[String], output: &str, value: f32) {
+        // Log format: `CauseA`, `CauseB` ---> `Result` (plus its value when fuzzy)
+        let cause_str = causes
+            .iter()
+            .map(|label| format!("`{}`", label))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let out_str = format!("`{}`", output);
+        let mut message = format!("{} ---> {}", cause_str, style(out_str).yellow().bold());
+        if value < 1.0 {
+            message.push_str(&format!(" ({:.2})", value));
+        }
+
+        let header = format!("[Tick {tick}]");
+        if self.logged == 0 || !self.layout.align_ticks {
+            println!("{header} {message}");
+        } else {
+            println!("{} {message}", " ".repeat(header.len()));
+        }
+        self.logged += 1;
+    }
+
+    fn vote(&mut self, tick: usize, scores: &[(String, f32)]) {
+        let scores = scores
+            .iter()
+            .map(|(label, score)| format!("`{}` = {:.2}", label, score))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("[Vote {}] {}", tick, scores);
+    }
+
+    fn lost(&mut self, label: &str) {
+        println!("[Lost]  `{}`", style(label).red().bold());
+    }
+
+    fn clock(&mut self, time: usize) {
+        println!("[Clock {}]", time);
+    }
+
+    fn decay(&mut self, age: usize) {
+        println!("[Decay {}]", age);
+    }
+
+    fn settled(&mut self) {
+        if self.layout.spacer {
+            println!(); // Spacer
+        }
+    }
+}
+
+/// Drops every event.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NullReporter;
+
+impl Reporter for NullReporter {}
+
+/// Progress as `tracing` events (`tracing` feature), for any subscriber
+/// or layer. Derivations are `INFO` events with `tick`, `output`, `causes`
+/// and `value` fields; `ponder`, `ponder_parallel` and `feed` run inside an
+/// `INFO` span of the same name, so a timing layer sees their duration.
+///
+/// ```no_run
+/// let mut mind = bi_core::Mind::new();
+/// mind.set_reporter(bi_core::TracingReporter);
+/// ```
+#[cfg(feature = "tracing")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingReporter;
+
+#[cfg(feature = "tracing")]
+impl Reporter for TracingReporter {
+    fn input(&mut self, label: &str, confidence: Option<f32>) {
+        tracing::info!(label, confidence = confidence.unwrap_or(1.0), "input");
+    }
+
+    fn inputs(&mut self, labels: &[&str]) {
+        tracing::info!(labels = ?labels, count = labels.len(), "inputs");
+    }
+
+    fn input_with_ttl(&mut self, label: &str, ttl: usize) {
+        tracing::info!(label, ttl, "input");
+    }
+
+    fn asserted_false(&mut self, label: &str, refused: bool) {
+        tracing::info!(label, refused, "asserted false");
+    }
+
+    fn withdrawn(&mut self, label: &str) {
+        tracing::info!(label, "withdrawn");
+    }
+
+    fn tick_started(&mut self, tick: usize) {
+        tracing::trace!(tick, "tick");
+    }
+
+    fn derived(&mut self, tick: usize, causes: &[String], output: &str, value: f32) {
+        tracing::info!(tick, output, ?causes, value, "derived");
+    }
+
+    fn vote(&mut self, tick: usize, scores: &[(String, f32)]) {
+        tracing::info!(tick, ?scores, "vote");
+    }
+
+    fn lost(&mut self, label: &str) {
+        tracing::info!(label, "lost");
+    }
+
+    fn clock(&mut self, time: usize) {
+        tracing::debug!(time, "clock");
+    }
+
+    fn decay(&mut self, age: usize) {
+        tracing::debug!(age, "decay");
+    }
+}
+
+/// The reporter of a mind. Clones share it: a copy reports to the same sink.
+#[derive(Clone)]
+pub(crate) struct SharedReporter(Arc<Lock<Box<dyn Reporter>>>); // Lock keeps `Mind: Sync`
+
+impl Default for SharedReporter {
+    #[cfg(feature = "std")]
+    fn default() -> Self {
+        Self::new(ConsoleReporter::default())
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn default() -> Self {
+        Self::new(NullReporter)
+    }
+}
+
+impl SharedReporter {
+    fn new(reporter: impl Reporter + 'static) -> Self {
+        Self(Arc::new(Lock::new(Box::new(reporter))))
+    }
+}
+
+impl fmt::Debug for SharedReporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedReporter")
+    }
+}
+
+impl<T> Mind<T> {
+    /// Send every progress event to `reporter` from now on.
+    pub fn set_reporter(&mut self, reporter: impl Reporter + 'static) {
+        self.reporter = SharedReporter::new(reporter);
+    }
+
+    /// Shorthand for a `ConsoleReporter` with `layout`.
+    #[cfg(feature = "std")]
+    pub fn set_layout(&mut self, layout: LogLayout) {
+        self.set_reporter(ConsoleReporter::new(layout));
+    }
+
+    /// Hands one event to the reporter. A reporter that panicked before
+    /// still gets the events after it.
+    pub(crate) fn report(&self, event: impl FnOnce(&mut dyn Reporter)) {
+        let mut reporter = self.reporter.0.lock();
+        event(reporter.as_mut());
+    }
+}
@@ -0,0 +1,83 @@
+//! Static rules (long-term memory).
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::symbols::FlagId;
+
+/// Logic Rule: a synaptic connection between flags.
+/// It defines what stimulates a result and what inhibits it.
+/// In a real BI, this is stored in the static "LogicRom".
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    pub triggers: Vec<FlagId>, // All must be present (AND logic)
+    pub any_of: Vec<FlagId>,   // At least `quorum` must be present, if any (OR logic)
+    pub quorum: usize,         // 1 for a plain OR group (K-of-N logic)
+    pub exclusive: bool,       // ... and at most one of them (XOR logic)
+    pub forbids: Vec<FlagId>,  // Any presence prevents output (NOT logic)
+    pub output: FlagId,        // The resulting concept
+    pub strict: bool,          // Forbids must be known false, not merely absent
+    pub priority: i32,         // Higher levels fire first within a tick (default 0)
+    pub min_confidence: f32,   // Fuzzy value needed to fire (default 0.0)
+    pub group: Option<usize>,  // Rule group, if any (see `learn_in_group`)
+    pub weight: f32,           // Salience: heavier derivations commit first (default 1.0)
+    pub added_at: u64,         // Rule version that added it (see `rule_changelog`)
+    pub modified_at: u64,      // ... and that last changed it
+}
+
+impl Rule {
+    /// Whether `learn` could have made this rule: no group members,
+    /// forbids or rule group, and every setting at its default.
+    pub(crate) fn is_plain(&self) -> bool {
+        self.any_of.is_empty()
+            && self.forbids.is_empty()
+            && self.group.is_none()
+            && !self.strict
+            && !self.exclusive
+            && self.priority == 0
+            && self.min_confidence == 0.0
+            && self.weight == 1.0
+    }
+
+    /// Whether `other` is the same logic, whenever either was edited.
+    pub fn same_logic(&self, other: &Rule) -> bool {
+        let unstamped = |rule: &Rule| Rule {
+            added_at: 0,
+            modified_at: 0,
+            ..rule.clone()
+        };
+        unstamped(self) == unstamped(other)
+    }
+}
+
+/// One edit of the rule base, as `rule_changelog` lists it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleChange {
+    pub version: u64,   // Rule version after the edit; one per edit, from 1
+    pub rule: usize,    // Index of the rule at that time
+    pub output: String, // Its output, to tell rules apart across removals
+    pub kind: RuleChangeKind,
+}
+
+/// What an edit did to a rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleChangeKind {
+    Added,    // `learn`, `rule`, ..., `import_rules`, `merge`
+    Removed,  // `unlearn`
+    Disabled, // `set_group_enabled(.., false)` on its group
+    Enabled,  // ... and back
+}
+
+/// A winner-take-all competition among several outputs.
+/// Each candidate scores the summed weight of its present triggers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vote {
+    pub candidates: Vec<(FlagId, Vec<(FlagId, f32)>)>, // (output, weighted triggers)
+}
+
+/// The outcome of one `Vote` in a tick.
+pub(crate) struct Ballot {
+    pub(crate) winner: FlagId,
+    pub(crate) causes: Vec<FlagId>, // The winner's present triggers
+    pub(crate) scores: Vec<(FlagId, f32)>, // Every candidate, in declaration order
+}
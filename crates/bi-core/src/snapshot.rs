@@ -0,0 +1,433 @@
+//! Checkpoints: save the dynamic memory, experiment, roll back.
+//!
+//! Snapshots live in process. `save_memory` (`std` feature) writes the
+//! same state to a JSON file in label form, so a later run with the same
+//! rules (loaded separately, in any id order) resumes where this one
+//! stopped.
+
+#[cfg(feature = "std")]
+use alloc::format;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "std")]
+use serde_json::{Map, Value, json};
+
+use crate::collections::HashMap;
+use crate::engine::{Mind, Source};
+use crate::flagset::FlagSet;
+use crate::symbols::FlagId;
+
+/// A frozen copy of a mind's dynamic memory: active flags with their
+/// sources, false assertions, confidences, lifetimes and latches. Rules
+/// and labels are not part of it. Shared internally, so clones are cheap.
+#[derive(Clone, Debug)]
+pub struct MemorySnapshot(Arc<Memory>);
+
+#[derive(Clone, Debug)]
+struct Memory {
+    active_memory: HashMap<FlagId, Source>,
+    known_false: FlagSet,
+    confidence: HashMap<FlagId, f32>,
+    activated_at: HashMap<FlagId, usize>,
+    lifetimes: HashMap<FlagId, usize>,
+    latched: FlagSet,
+    blocked: HashMap<FlagId, Vec<FlagId>>,
+    age: usize,
+}
+
+/// What changed between two snapshots, in label form and sorted by label:
+/// flags that became active, flags that went away, and the confidence of
+/// flags active in both that moved (fuzzy minds only).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MemoryDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub confidence: Vec<(String, f32, f32)>, // (label, before, after)
+}
+
+impl MemoryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.confidence.is_empty()
+    }
+}
+
+/// One line per change: `+ \`Alarm\``, `- \`LightOn\`` and
+/// `~ \`Smoke\` 0.60 -> 0.90`.
+impl fmt::Display for MemoryDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for label in &self.added {
+            writeln!(f, "+ `{}`", label)?;
+        }
+        for label in &self.removed {
+            writeln!(f, "- `{}`", label)?;
+        }
+        for (label, before, after) in &self.confidence {
+            writeln!(f, "~ `{}` {:.2} -> {:.2}", label, before, after)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why `save_memory` or `load_memory` failed. Memory is left as it was.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MemoryFileError {
+    /// The file could not be written or read.
+    Io { path: PathBuf, message: String },
+    /// The file is not a memory file.
+    Format { path: PathBuf, message: String },
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for MemoryFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryFileError::Io { path, message } | MemoryFileError::Format { path, message } => {
+                write!(f, "{}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::error::Error for MemoryFileError {}
+
+impl MemorySnapshot {
+    /// Number of flags that were active.
+    pub fn len(&self) -> usize {
+        self.0.active_memory.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.active_memory.is_empty()
+    }
+}
+
+impl<T> Mind<T> {
+    /// Checkpoint the dynamic memory (see `restore`).
+    pub fn snapshot(&self) -> MemorySnapshot {
+        MemorySnapshot(Arc::new(Memory {
+            active_memory: self.active_memory.clone(),
+            known_false: self.known_false.clone(),
+            confidence: self.confidence.clone(),
+            activated_at: self.activated_at.clone(),
+            lifetimes: self.lifetimes.clone(),
+            latched: self.latched.clone(),
+            blocked: self.blocked.clone(),
+            age: self.age,
+        }))
+    }
+
+    /// How memory changed from `before` to `after`, labeled through this
+    /// mind (both should be snapshots of it).
+    pub fn diff(&self, before: &MemorySnapshot, after: &MemorySnapshot) -> MemoryDiff {
+        let (before, after) = (&before.0, &after.0);
+        let strength = |memory: &Memory, id: &FlagId| *memory.confidence.get(id).unwrap_or(&1.0);
+        let sorted = |mut labels: Vec<String>| {
+            labels.sort();
+            labels
+        };
+
+        let added = after
+            .active_memory
+            .keys()
+            .filter(|id| !before.active_memory.contains_key(*id))
+            .map(|id| self.label(*id))
+            .collect();
+        let removed = before
+            .active_memory
+            .keys()
+            .filter(|id| !after.active_memory.contains_key(*id))
+            .map(|id| self.label(*id))
+            .collect();
+        let mut confidence: Vec<(String, f32, f32)> = after
+            .active_memory
+            .keys()
+            .filter(|id| before.active_memory.contains_key(*id))
+            .filter_map(|id| {
+                let (was, now) = (strength(before, id), strength(after, id));
+                (was != now).then(|| (self.label(*id), was, now))
+            })
+            .collect();
+        confidence.sort_by(|a, b| a.0.cmp(&b.0));
+
+        MemoryDiff {
+            added: sorted(added),
+            removed: sorted(removed),
+            confidence,
+        }
+    }
+
+    /// Roll the dynamic memory back to `snap`, keeping rules, labels and the
+    /// activation counters. The change is reported by the next `tick_diff`
+    /// like any other. Snapshots from a mind with other labels mean nothing here.
+    pub fn restore(&mut self, snap: MemorySnapshot) {
+        let memory = Arc::unwrap_or_clone(snap.0);
+
+        let before: FlagSet = self.active_ids();
+        let after: FlagSet = memory.active_memory.keys().copied().collect();
+        for id in before.difference(&after) {
+            self.note_removed(id);
+        }
+        for id in after.difference(&before) {
+            self.note_added(id);
+        }
+        self.flush_diff(0);
+
+        self.active_memory = memory.active_memory;
+        self.known_false = memory.known_false;
+        self.confidence = memory.confidence;
+        self.activated_at = memory.activated_at;
+        self.lifetimes = memory.lifetimes;
+        self.latched = memory.latched;
+        self.blocked = memory.blocked;
+        self.age = memory.age;
+        self.dirty_rules = (0..self.rules.len()).collect();
+    }
+
+    /// Writes the dynamic memory to `path` as JSON, every flag by label:
+    /// active flags with their sources, confidences and lifetimes, false
+    /// assertions, blocked derivations, latched inputs and the decay age.
+    /// Rules, payloads, the trigger mode, the clock and the counters are
+    /// not saved, nor the ticks of `derived_at` (loaded flags read 0).
+    #[cfg(feature = "std")]
+    pub fn save_memory(&self, path: &Path) -> Result<(), MemoryFileError> {
+        let labels =
+            |ids: &[FlagId]| -> Vec<String> { ids.iter().map(|id| self.label(*id)).collect() };
+
+        let mut active: Vec<(String, Value)> = self
+            .active_memory
+            .iter()
+            .map(|(id, source)| {
+                let mut entry = Map::new();
+                entry.insert("label".into(), json!(self.label(*id)));
+                match source {
+                    Source::Input => entry.insert("source".into(), json!("input")),
+                    Source::Derived { justifications } => {
+                        let justifications: Vec<Vec<String>> =
+                            justifications.iter().map(|causes| labels(causes)).collect();
+                        entry.insert("justifications".into(), json!(justifications))
+                    }
+                };
+                if let Some(confidence) = self.confidence.get(id) {
+                    entry.insert("confidence".into(), json!(confidence));
+                }
+                if let Some(ttl) = self.lifetimes.get(id) {
+                    entry.insert("lifetime".into(), json!(ttl));
+                }
+                (self.label(*id), Value::Object(entry))
+            })
+            .collect();
+        active.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut known_false: Vec<String> =
+            self.known_false.iter().map(|id| self.label(id)).collect();
+        known_false.sort();
+        let latched = self.latched();
+        let blocked: Map<String, Value> = self
+            .blocked
+            .iter()
+            .map(|(id, blockers)| (self.label(*id), json!(labels(blockers))))
+            .collect();
+
+        let memory = json!({
+            "age": self.age,
+            "active": active.into_iter().map(|(_, entry)| entry).collect::<Vec<_>>(),
+            "false": known_false,
+            "blocked": blocked,
+            "latched": latched,
+        });
+        let text = serde_json::to_string_pretty(&memory).unwrap_or_default(); // Plain JSON values always serialize
+        std::fs::write(path, text + "\n").map_err(|err| MemoryFileError::Io {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })
+    }
+
+    /// Replaces the dynamic memory with the one `save_memory` wrote to
+    /// `path`, like `restore`; labels this mind has not seen get fresh ids.
+    /// A derived flag that no current rule outputs cannot be justified any
+    /// more: it is kept, as an input. Returns the labels kept that way.
+    #[cfg(feature = "std")]
+    pub fn load_memory(&mut self, path: &Path) -> Result<Vec<String>, MemoryFileError> {
+        let format = |message: String| MemoryFileError::Format {
+            path: path.to_path_buf(),
+            message,
+        };
+        let text = std::fs::read_to_string(path).map_err(|err| MemoryFileError::Io {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        let file: SavedMemory = serde_json::from_str::<Value>(&text)
+            .map_err(|err| format(err.to_string()))
+            .and_then(|value| SavedMemory::parse(&value).map_err(format))?;
+
+        let mut memory = Memory {
+            active_memory: HashMap::new(),
+            known_false: FlagSet::new(),
+            confidence: HashMap::new(),
+            activated_at: HashMap::new(),
+            lifetimes: HashMap::new(),
+            latched: FlagSet::new(),
+            blocked: HashMap::new(),
+            age: file.age,
+        };
+        let mut orphans = Vec::new();
+        for flag in file.active {
+            let id = self.id(&flag.label);
+            let source = match flag.justifications {
+                Some(_) if !self.rules.iter().any(|rule| rule.output == id) => {
+                    orphans.push(flag.label);
+                    Source::Input
+                }
+                Some(justifications) => Source::Derived {
+                    justifications: justifications
+                        .iter()
+                        .map(|causes| causes.iter().map(|label| self.id(label)).collect())
+                        .collect(),
+                },
+                None => Source::Input,
+            };
+            memory.active_memory.insert(id, source);
+            if let Some(confidence) = flag.confidence {
+                memory.confidence.insert(id, confidence);
+            }
+            if let Some(ttl) = flag.lifetime {
+                memory.lifetimes.insert(id, ttl);
+            }
+            self.clear_payload(id);
+        }
+        for label in &file.known_false {
+            let id = self.id(label);
+            memory.known_false.insert(id);
+        }
+        for label in &file.latched {
+            let id = self.id(label);
+            memory.latched.insert(id);
+        }
+        for (label, blockers) in &file.blocked {
+            let id = self.id(label);
+            let blockers = blockers.iter().map(|label| self.id(label)).collect();
+            memory.blocked.insert(id, blockers);
+        }
+
+        self.restore(MemorySnapshot(Arc::new(memory)));
+        Ok(orphans)
+    }
+}
+
+/// A memory file, read but not yet resolved to ids.
+#[cfg(feature = "std")]
+struct SavedMemory {
+    age: usize,
+    active: Vec<SavedFlag>,
+    known_false: Vec<String>,
+    blocked: Vec<(String, Vec<String>)>,
+    latched: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+struct SavedFlag {
+    label: String,
+    justifications: Option<Vec<Vec<String>>>, // None for an input
+    confidence: Option<f32>,
+    lifetime: Option<usize>,
+}
+
+#[cfg(feature = "std")]
+impl SavedMemory {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let age = match value.get("age") {
+            None => 0,
+            Some(age) => age.as_u64().ok_or("`age` must be a count")? as usize,
+        };
+        let active = array(value, "active")?
+            .iter()
+            .map(SavedFlag::parse)
+            .collect::<Result<_, _>>()?;
+        let known_false = strings(value.get("false").unwrap_or(&json!([])), "false")?;
+        let latched = strings(value.get("latched").unwrap_or(&json!([])), "latched")?;
+        let blocked = match value.get("blocked") {
+            None => Vec::new(),
+            Some(blocked) => blocked
+                .as_object()
+                .ok_or("`blocked` must be an object")?
+                .iter()
+                .map(|(label, blockers)| Ok((label.clone(), strings(blockers, "blocked")?)))
+                .collect::<Result<_, String>>()?,
+        };
+        Ok(Self {
+            age,
+            active,
+            known_false,
+            blocked,
+            latched,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl SavedFlag {
+    fn parse(value: &Value) -> Result<Self, String> {
+        let label = value
+            .get("label")
+            .and_then(Value::as_str)
+            .ok_or("every active flag needs a `label`")?
+            .to_string();
+        let justifications = match value.get("justifications") {
+            None => None,
+            Some(list) => Some(
+                list.as_array()
+                    .ok_or(format!("`{}`: `justifications` must be an array", label))?
+                    .iter()
+                    .map(|causes| strings(causes, "justifications"))
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+        let confidence = match value.get("confidence") {
+            None => None,
+            Some(c) => Some(c.as_f64().ok_or(format!("`{}`: bad confidence", label))? as f32),
+        };
+        let lifetime = match value.get("lifetime") {
+            None => None,
+            Some(t) => Some(t.as_u64().ok_or(format!("`{}`: bad lifetime", label))? as usize),
+        };
+        Ok(Self {
+            label,
+            justifications,
+            confidence,
+            lifetime,
+        })
+    }
+}
+
+/// The array under `key`; missing reads as empty.
+#[cfg(feature = "std")]
+fn array<'a>(value: &'a Value, key: &str) -> Result<&'a [Value], String> {
+    match value.get(key) {
+        None => Ok(&[]),
+        Some(list) => list
+            .as_array()
+            .map(Vec::as_slice)
+            .ok_or(format!("`{}` must be an array", key)),
+    }
+}
+
+/// `value` as a list of labels.
+#[cfg(feature = "std")]
+fn strings(value: &Value, key: &str) -> Result<Vec<String>, String> {
+    value
+        .as_array()
+        .and_then(|list| {
+            list.iter()
+                .map(|s| s.as_str().map(str::to_string))
+                .collect()
+        })
+        .ok_or(format!("`{}` must list labels", key))
+}
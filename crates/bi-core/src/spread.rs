@@ -0,0 +1,82 @@
+//! Associative Recall: spreading activation over the rule graph.
+//!
+//! Unlike `tick`, nothing is derived or stored: starting from a seed,
+//! strength flows to the flags that share a rule with it, weaker with
+//! every hop. The result ranks which concepts are "nearby" a stimulus.
+
+use alloc::vec::Vec;
+
+use crate::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::engine::Mind;
+use crate::symbols::FlagId;
+
+impl<T> Mind<T> {
+    /// Activation reaching each flag from `seed` (which holds 1.0).
+    ///
+    /// Two flags are related when one rule or vote mentions both as a
+    /// trigger, group member or output (forbids relate nothing: they
+    /// inhibit). Breadth-first, each flag is reached once, at its shortest
+    /// hop count: it gets `decay` times the summed activation of its
+    /// related flags one hop closer, at most 1.0. The search stops at flags
+    /// under `floor`, which are left out. `decay` is clamped to [0, 1];
+    /// an unknown seed gives an empty map. Memory is not consulted.
+    pub fn spread_activation(&self, seed: &str, decay: f32, floor: f32) -> HashMap<FlagId, f32> {
+        let mut reached = HashMap::new();
+        let Some(seed) = self.symbols.get(seed) else {
+            return reached;
+        };
+        let decay = decay.clamp(0.0, 1.0);
+        let related = self.relations();
+
+        reached.insert(seed, 1.0);
+        let mut frontier = BTreeSet::from([seed]);
+        while !frontier.is_empty() {
+            let mut incoming: BTreeMap<FlagId, f32> = BTreeMap::new();
+            for id in &frontier {
+                let strength = reached[id] * decay;
+                let neighbours = related.get(id).into_iter().flatten();
+                for &next in neighbours.filter(|next| !reached.contains_key(*next)) {
+                    *incoming.entry(next).or_default() += strength;
+                }
+            }
+            frontier.clear();
+            for (id, strength) in incoming {
+                let strength = strength.min(1.0);
+                if strength >= floor {
+                    reached.insert(id, strength);
+                    frontier.insert(id);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Flag -> the flags sharing a rule or vote with it.
+    fn relations(&self) -> HashMap<FlagId, BTreeSet<FlagId>> {
+        let mut groups: Vec<Vec<FlagId>> = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let mut members: Vec<FlagId> =
+                    rule.triggers.iter().chain(&rule.any_of).copied().collect();
+                members.push(rule.output);
+                members
+            })
+            .collect();
+        groups.extend(self.votes.iter().map(|vote| {
+            vote.candidates
+                .iter()
+                .flat_map(|(output, triggers)| triggers.iter().map(|(t, _)| *t).chain([*output]))
+                .collect()
+        }));
+
+        let mut related: HashMap<FlagId, BTreeSet<FlagId>> = HashMap::new();
+        for members in &groups {
+            for &a in members {
+                let others = members.iter().copied().filter(|&b| b != a);
+                related.entry(a).or_default().extend(others);
+            }
+        }
+        related
+    }
+}
@@ -0,0 +1,387 @@
+//! Inference Metrics: how much work reaching a fixpoint took, and how big
+//! the knowledge it worked on is.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::collections::{BTreeMap, HashMap};
+use crate::decay::FixpointOutcome;
+use crate::engine::{Mind, Source};
+use crate::flagset::FlagSet;
+use crate::strata::LogicError;
+use crate::symbols::FlagId;
+
+/// Work done by ticks, either over one `ponder_with_stats` or as the
+/// running total of a mind (`stats`). A rule set needing many ticks or
+/// evaluations to settle shows up here long before it shows up in a log.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub ticks_run: usize,
+    pub facts_derived: usize, // Every logged derivation, vote winners included
+    pub rules_evaluated: usize, // Candidate rules checked (the trigger index skips the rest)
+    pub time_elapsed: Duration, // Inside `tick`, logging included, as `set_timer` reads it
+}
+
+/// Size and shape of a mind (see `introspect`), for tuning a rule base.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Introspection {
+    pub labels: usize, // Symbol table size
+    pub rules: usize,
+    pub votes: usize,
+    pub active: usize, // Active flags, inputs included
+    pub inputs: usize,
+    pub max_triggers: usize, // Triggers and group members of the widest rule
+    pub mean_triggers: f32,  // ... and per rule on average (0.0 without rules)
+    pub fan_out: Vec<(usize, usize)>, // (rules a flag triggers, flags that many), ascending
+    pub most_referenced: Vec<(String, usize)>, // Up to five flags by mentions in rules and votes
+}
+
+/// Scenarios a mind was observed in, for empirical input/output
+/// associations (see `io_matrix`). Each scenario keeps the inputs that were
+/// present and the derived flags they ended up with.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profiler {
+    scenarios: Vec<(Vec<String>, Vec<String>)>, // (inputs, derived), sorted labels
+}
+
+/// Over the scenarios of a `Profiler`: for each input, in how many it was
+/// present, and the share of those in which each derived flag was active.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IoMatrix {
+    pub scenarios: usize,
+    pub present: BTreeMap<String, usize>, // Scenarios per input
+    pub frequency: BTreeMap<String, BTreeMap<String, f32>>, // Input -> output -> share
+}
+
+impl IoMatrix {
+    /// Share of the scenarios with `input` present in which `output` was
+    /// active; 0.0 if one of them never occurred.
+    pub fn frequency(&self, input: &str, output: &str) -> f32 {
+        self.frequency
+            .get(input)
+            .and_then(|outputs| outputs.get(output))
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records where `mind` stands now (its inputs and derived flags)
+    /// as one scenario; ponder it first.
+    pub fn record<T>(&mut self, mind: &Mind<T>) {
+        self.scenarios.push((mind.inputs(), mind.derived()));
+    }
+
+    /// Records the fixpoint `inputs` alone reach under the rules of `mind`,
+    /// its memory left untouched. Unknown labels are ignored.
+    pub fn run<T>(&mut self, mind: &Mind<T>, inputs: &[&str]) {
+        let given: FlagSet = inputs.iter().filter_map(|n| mind.symbols.get(n)).collect();
+        let mut present: Vec<String> = given.iter().map(|id| mind.label(id)).collect();
+        let mut derived: Vec<String> = mind
+            .settle(given.clone())
+            .into_iter()
+            .filter(|id| !given.contains(id))
+            .map(|id| mind.label(id))
+            .collect();
+        present.sort();
+        derived.sort();
+        self.scenarios.push((present, derived));
+    }
+
+    /// Number of scenarios recorded.
+    pub fn len(&self) -> usize {
+        self.scenarios.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenarios.is_empty()
+    }
+
+    /// For each input, how often each derived flag was active when it was
+    /// present: observed behavior next to the static rule graph. A share of
+    /// 1.0 means the output followed that input in every scenario.
+    pub fn io_matrix(&self) -> IoMatrix {
+        let mut present: BTreeMap<String, usize> = BTreeMap::new();
+        let mut counts: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        for (inputs, derived) in &self.scenarios {
+            for input in inputs {
+                *present.entry(input.clone()).or_default() += 1;
+                let row = counts.entry(input.clone()).or_default();
+                for output in derived {
+                    *row.entry(output.clone()).or_default() += 1;
+                }
+            }
+        }
+        let frequency = counts
+            .into_iter()
+            .map(|(input, row)| {
+                let seen = present[&input] as f32;
+                let row = row
+                    .into_iter()
+                    .map(|(output, count)| (output, count as f32 / seen))
+                    .collect();
+                (input, row)
+            })
+            .collect();
+        IoMatrix {
+            scenarios: self.scenarios.len(),
+            present,
+            frequency,
+        }
+    }
+}
+
+/// A small two-column table, one quantity per line.
+impl fmt::Display for Introspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fan_out = self
+            .fan_out
+            .iter()
+            .map(|(rules, flags)| format!("{} rule(s): {} flag(s)", rules, flags))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let most_referenced = self
+            .most_referenced
+            .iter()
+            .map(|(label, count)| format!("`{}` ({})", label, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(f, "{:<15} {}", "labels", self.labels)?;
+        writeln!(f, "{:<15} {} (votes {})", "rules", self.rules, self.votes)?;
+        writeln!(
+            f,
+            "{:<15} {} of {} ({} inputs)",
+            "active", self.active, self.labels, self.inputs
+        )?;
+        writeln!(
+            f,
+            "{:<15} max {}, mean {:.2}",
+            "triggers/rule", self.max_triggers, self.mean_triggers
+        )?;
+        writeln!(f, "{:<15} {}", "fan-out", fan_out)?;
+        writeln!(f, "{:<15} {}", "most referenced", most_referenced)
+    }
+}
+
+/// Time since the first call, on std's monotonic clock: the default timer.
+#[cfg(feature = "std")]
+pub(crate) fn monotonic() -> Duration {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed()
+}
+
+/// Without std there is no clock to read: `time_elapsed` stays zero
+/// until `set_timer` gives one.
+#[cfg(not(feature = "std"))]
+pub(crate) fn monotonic() -> Duration {
+    Duration::ZERO
+}
+
+impl<T> Mind<T> {
+    /// Read the time for `Stats::time_elapsed` from `now`, e.g. a hardware
+    /// timer on a target without std. It must never go backwards; only
+    /// differences between two readings count.
+    pub fn set_timer(&mut self, now: fn() -> Duration) {
+        self.timer = now;
+    }
+
+    /// How large this mind is: labels, rules and votes, active memory, the
+    /// triggers per rule, how many rules each flag triggers (fan-out, from
+    /// the trigger index view: triggers and group members), and the flags
+    /// mentioned most often in any role. Cheap enough to call at runtime.
+    pub fn introspect(&self) -> Introspection {
+        let widths: Vec<usize> = self
+            .rules
+            .iter()
+            .map(|rule| rule.triggers.len() + rule.any_of.len())
+            .collect();
+        let mean_triggers = if widths.is_empty() {
+            0.0
+        } else {
+            widths.iter().sum::<usize>() as f32 / widths.len() as f32
+        };
+
+        let mut triggered: HashMap<FlagId, usize> = HashMap::new();
+        let mut mentions: HashMap<FlagId, usize> = HashMap::new();
+        for rule in &self.rules {
+            for &id in rule.triggers.iter().chain(&rule.any_of) {
+                *triggered.entry(id).or_default() += 1;
+            }
+            let read = rule
+                .triggers
+                .iter()
+                .chain(&rule.any_of)
+                .chain(&rule.forbids);
+            for &id in read.chain([&rule.output]) {
+                *mentions.entry(id).or_default() += 1;
+            }
+        }
+        for vote in &self.votes {
+            for (output, triggers) in &vote.candidates {
+                for &id in triggers.iter().map(|(t, _)| t).chain([output]) {
+                    *mentions.entry(id).or_default() += 1;
+                }
+            }
+        }
+        let mut fan_out: BTreeMap<usize, usize> = BTreeMap::new();
+        for id in self.symbols.ids() {
+            *fan_out
+                .entry(triggered.get(&id).copied().unwrap_or(0))
+                .or_default() += 1;
+        }
+        let mut most_referenced: Vec<(String, usize)> = mentions
+            .into_iter()
+            .map(|(id, count)| (self.label(id), count))
+            .collect();
+        most_referenced.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        most_referenced.truncate(5);
+
+        Introspection {
+            labels: self.symbols.len(),
+            rules: self.rules.len(),
+            votes: self.votes.len(),
+            active: self.active_memory.len(),
+            inputs: self
+                .active_memory
+                .values()
+                .filter(|source| matches!(source, Source::Input))
+                .count(),
+            max_triggers: widths.iter().copied().max().unwrap_or(0),
+            mean_triggers,
+            fan_out: fan_out.into_iter().collect(),
+            most_referenced,
+        }
+    }
+
+    /// `ponder`, returning the work this run alone took.
+    pub fn ponder_with_stats(&mut self) -> Stats {
+        let before = self.stats.clone();
+        self.ponder();
+        self.stats_since(&before)
+    }
+
+    /// `run_to_fixpoint` on a budget: memory must be final within
+    /// `max_ticks` decaying steps, the quiet one that confirms it included
+    /// (a chain of n derivations needs n + 1). Returns the work this run
+    /// took. Without mortal inputs or negation through a cycle this is
+    /// `ponder` with a bound.
+    ///
+    /// The bound keeps a huge or runaway rule base (e.g. generated rules)
+    /// from stalling an embedder that must answer in time; a rule set that
+    /// alternates under truth maintenance is reported as soon as memory
+    /// repeats, without waiting for the budget to run out.
+    ///
+    /// # Errors
+    /// `LogicError::NonTerminating`, with the flags that come and go if
+    /// memory oscillates, or else the ones the last allowed step added or
+    /// removed. Memory keeps its current state; `ponder_bounded` again to
+    /// go on.
+    pub fn ponder_bounded(&mut self, max_ticks: usize) -> Result<Stats, LogicError> {
+        let before = self.stats.clone();
+        let flags = match self.run_steps(Some(max_ticks)) {
+            Ok(FixpointOutcome::Stable) => {
+                self.report(|r| r.settled());
+                return Ok(self.stats_since(&before));
+            }
+            Ok(FixpointOutcome::Oscillating(flags)) | Err(flags) => flags,
+        };
+        let mut flags: Vec<String> = flags.iter().map(|id| self.label(*id)).collect();
+        flags.sort();
+        Err(LogicError::NonTerminating {
+            ticks: self.stats.ticks_run - before.ticks_run,
+            flags,
+        })
+    }
+
+    fn stats_since(&self, before: &Stats) -> Stats {
+        Stats {
+            ticks_run: self.stats.ticks_run - before.ticks_run,
+            facts_derived: self.stats.facts_derived - before.facts_derived,
+            rules_evaluated: self.stats.rules_evaluated - before.rules_evaluated,
+            time_elapsed: self.stats.time_elapsed - before.time_elapsed,
+        }
+    }
+
+    /// Totals over every tick so far; `reset_memory` does not clear them.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    #[test]
+    fn bounded_ponder_names_the_flags_alternating_under_maintenance() {
+        let mut mind = quiet();
+        mind.rule(&["Go"], &["B"], "A"); // A until B, B from A: never settles
+        mind.learn(&["A"], "B");
+        mind.inject(&["Go"]);
+        match mind.ponder_bounded(100) {
+            Err(LogicError::NonTerminating { ticks, flags }) => {
+                assert!(ticks < 100, "the repeat is caught before the budget");
+                assert_eq!(flags, ["A", "B"]);
+            }
+            other => panic!("expected NonTerminating, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bounded_ponder_counts_the_confirming_tick() {
+        let mut mind = quiet();
+        mind.learn(&["A"], "B");
+        mind.learn(&["B"], "C");
+        mind.inject(&["A"]);
+        assert_eq!(
+            mind.ponder_bounded(2),
+            Err(LogicError::NonTerminating {
+                ticks: 2,
+                flags: vec!["C".to_string()],
+            })
+        );
+        assert_eq!(mind.ponder_bounded(1).map(|stats| stats.ticks_run), Ok(1));
+        assert!(mind.is_active("C"));
+    }
+
+    #[test]
+    fn io_matrix_counts_outputs_per_present_input() {
+        let mut mind = quiet();
+        mind.learn(&["Stove"], "Hot");
+        mind.learn(&["Stove", "Pot"], "Boiling");
+        let mut profiler = Profiler::new();
+        profiler.run(&mind, &["Stove"]);
+        profiler.run(&mind, &["Stove", "Pot"]);
+        mind.inject(&["Pot"]);
+        mind.ponder();
+        profiler.record(&mind);
+
+        let matrix = profiler.io_matrix();
+        assert_eq!(matrix.scenarios, 3);
+        assert_eq!(matrix.present["Stove"], 2);
+        assert_eq!(matrix.present["Pot"], 2);
+        assert_eq!(matrix.frequency("Stove", "Hot"), 1.0);
+        assert_eq!(matrix.frequency("Stove", "Boiling"), 0.5);
+        assert_eq!(matrix.frequency("Pot", "Boiling"), 0.5);
+        assert_eq!(matrix.frequency("Pot", "Hot"), 0.5);
+        assert_eq!(matrix.frequency("Lid", "Hot"), 0.0);
+    }
+}
@@ -0,0 +1,234 @@
+//! Stratified Negation: inhibitors settle before the rules they inhibit.
+//!
+//! Every flag gets a stratum: at least that of its triggers, and above
+//! that of its forbids. Rules fire lowest stratum first, and a stratum only
+//! gets its turn in a tick once no lower one derives anything, so a forbid
+//! is final by the time a rule reads it. Positive-only knowledge is a
+//! single stratum and runs exactly as before.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+
+use crate::collections::HashMap;
+use crate::engine::Mind;
+use crate::symbols::FlagId;
+
+/// A rule set whose meaning depends on evaluation order, or that does not
+/// settle within its budget.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogicError {
+    /// Flags that (indirectly) inhibit each other, sorted by label:
+    /// no order settles every forbid before it is read.
+    NegationCycle(Vec<String>),
+    /// Memory was not final after `ticks` ticks (see `ponder_bounded`):
+    /// `flags` are the ones oscillating, or else the ones the last tick
+    /// changed, sorted.
+    NonTerminating { ticks: usize, flags: Vec<String> },
+}
+
+impl fmt::Display for LogicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogicError::NegationCycle(flags) => {
+                let flags: Vec<String> = flags.iter().map(|l| format!("`{}`", l)).collect();
+                write!(f, "negation through a cycle: {}", flags.join(", "))
+            }
+            LogicError::NonTerminating { ticks, flags } => {
+                let flags: Vec<String> = flags.iter().map(|l| format!("`{}`", l)).collect();
+                write!(
+                    f,
+                    "not stable after {} ticks, still changing {}",
+                    ticks,
+                    flags.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for LogicError {}
+
+impl<T> Mind<T> {
+    /// Checks that the rules are stratifiable, i.e. no flag inhibits itself
+    /// through a cycle of rules. Unstratifiable rules still run, with each
+    /// such cycle treated as a single stratum (order-dependent, as before).
+    pub fn validate(&self) -> Result<(), LogicError> {
+        match self.stratify().1 {
+            Some(cycle) => {
+                let mut labels: Vec<String> = cycle.iter().map(|id| self.label(*id)).collect();
+                labels.sort();
+                Err(LogicError::NegationCycle(labels))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// The stratum `label` is settled in; 0 for inputs and unknown labels.
+    pub fn stratum(&self, label: &str) -> usize {
+        let strata = self.stratify().0;
+        self.symbols
+            .get(label)
+            .and_then(|id| strata.get(&id).copied())
+            .unwrap_or(0)
+    }
+
+    /// Stratum of each rule (that of its output), by rule index.
+    pub(crate) fn rule_strata(&self) -> Vec<usize> {
+        let strata = self.stratify().0;
+        self.rules
+            .iter()
+            .map(|rule| strata.get(&rule.output).copied().unwrap_or(0))
+            .collect()
+    }
+
+    /// Strata of the flags rules produce, and the members of one
+    /// strongly connected component holding a forbid edge, if any.
+    fn stratify(&self) -> (HashMap<FlagId, usize>, Option<Vec<FlagId>>) {
+        // Edges source -> output; `true` marks a forbid.
+        let mut edges: Vec<(FlagId, FlagId, bool)> = Vec::new();
+        for rule in &self.rules {
+            for &t in rule
+                .triggers
+                .iter()
+                .chain(&rule.any_of)
+                .chain(&self.gate(rule))
+            {
+                edges.push((t, rule.output, false));
+            }
+            for &f in &rule.forbids {
+                edges.push((f, rule.output, true));
+            }
+        }
+        for vote in &self.votes {
+            for (output, triggers) in &vote.candidates {
+                edges.extend(triggers.iter().map(|(t, _)| (*t, *output, false)));
+            }
+        }
+
+        let mut nodes: Vec<FlagId> = edges.iter().flat_map(|&(a, b, _)| [a, b]).collect();
+        nodes.sort();
+        nodes.dedup();
+        let index: HashMap<FlagId, usize> =
+            nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut forward = vec![Vec::new(); nodes.len()];
+        let mut backward = vec![Vec::new(); nodes.len()];
+        for &(a, b, _) in &edges {
+            forward[index[&a]].push(index[&b]);
+            backward[index[&b]].push(index[&a]);
+        }
+
+        // Kosaraju: finish order on the graph, then components on the
+        // reversed graph in reverse finish order, i.e. in topological order.
+        let mut finished = Vec::with_capacity(nodes.len());
+        let mut seen = vec![false; nodes.len()];
+        for start in 0..nodes.len() {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut stack = vec![(start, 0)];
+            while let Some((node, next)) = stack.pop() {
+                if let Some(&child) = forward[node].get(next) {
+                    stack.push((node, next + 1));
+                    if !seen[child] {
+                        seen[child] = true;
+                        stack.push((child, 0));
+                    }
+                } else {
+                    finished.push(node);
+                }
+            }
+        }
+        let mut component = vec![usize::MAX; nodes.len()];
+        let mut components = 0;
+        for &start in finished.iter().rev() {
+            if component[start] != usize::MAX {
+                continue;
+            }
+            component[start] = components;
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for &parent in &backward[node] {
+                    if component[parent] == usize::MAX {
+                        component[parent] = components;
+                        stack.push(parent);
+                    }
+                }
+            }
+            components += 1;
+        }
+
+        // Longest path over the components, forbids counting one stratum.
+        let mut incoming = vec![Vec::new(); components];
+        let mut cycle = None;
+        for &(a, b, negative) in &edges {
+            let (from, to) = (component[index[&a]], component[index[&b]]);
+            if from != to {
+                incoming[to].push((from, negative));
+            } else if negative && cycle.is_none() {
+                cycle = Some(from);
+            }
+        }
+        let mut level = vec![0; components];
+        for c in 0..components {
+            level[c] = incoming[c]
+                .iter()
+                .map(|&(from, negative)| level[from] + usize::from(negative))
+                .max()
+                .unwrap_or(0);
+        }
+
+        let strata = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, level[component[i]]))
+            .collect();
+        let cycle = cycle.map(|c| {
+            (0..nodes.len())
+                .filter(|&i| component[i] == c)
+                .map(|i| nodes[i])
+                .collect()
+        });
+        (strata, cycle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    #[test]
+    fn a_late_inhibitor_still_suppresses() {
+        let mut park = quiet();
+        park.learn(&["Spark"], "Flame");
+        park.learn(&["Flame"], "Fire");
+        park.rule(&["Sunny"], &["Fire"], "Picnic");
+        assert_eq!(park.validate(), Ok(()));
+        assert!(park.stratum("Picnic") > park.stratum("Fire"));
+
+        park.inject(&["Spark", "Sunny"]);
+        park.ponder();
+        assert!(park.is_active("Fire"));
+        assert!(!park.is_active("Picnic"));
+    }
+
+    #[test]
+    fn negation_through_a_cycle_is_rejected() {
+        let mut mind = quiet();
+        mind.rule(&["Seed"], &["B"], "A");
+        mind.rule(&["Seed"], &["A"], "B");
+        assert_eq!(
+            mind.validate(),
+            Err(LogicError::NegationCycle(vec!["A".into(), "B".into()]))
+        );
+    }
+}
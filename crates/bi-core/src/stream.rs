@@ -0,0 +1,40 @@
+//! Streaming Input: one stimulus at a time, for minds that never stop.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::collections::HashSet;
+use crate::engine::{Mind, Source};
+
+impl<T> Mind<T> {
+    /// Inject one stimulus on top of the current memory and re-stabilize.
+    /// Only the rules the stimulus touches wake up (the trigger index), so a
+    /// long-running mind pays for what changed, not for what it remembers.
+    ///
+    /// Returns the labels this stimulus newly derived, in derivation order.
+    pub fn feed(&mut self, label: &str) -> Vec<String> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("feed", label).entered();
+        self.inject_one(label, 1.0);
+        self.report(|r| r.input(label, None));
+
+        let mut seen = self.last_diff.clone();
+        let mut added = Vec::new();
+        let mut tick = 1;
+        while self.tick(tick) {
+            if self.last_diff != seen {
+                seen = self.last_diff.clone();
+                added.extend(seen.added.iter().copied());
+            }
+            tick += 1;
+        }
+        self.report(|r| r.settled());
+
+        // Votes can swap a flag out again within the same run
+        let mut unique = HashSet::new();
+        added.retain(|id| {
+            matches!(self.active_memory.get(id), Some(Source::Derived { .. })) && unique.insert(*id)
+        });
+        added.into_iter().map(|id| self.label(id)).collect()
+    }
+}
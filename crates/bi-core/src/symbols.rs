@@ -0,0 +1,214 @@
+//! Symbol table: human-readable labels <-> machine flag ids.
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::collections::HashMap;
+
+pub type FlagId = u32;
+
+/// Joins a namespace and a label: `kitchen::Ready`.
+pub const NAMESPACE_SEPARATOR: &str = "::";
+
+/// `label` inside `namespace`. A label that is already qualified is kept
+/// as it is, so `qualify("kitchen", "safety::Ready")` stays shared.
+pub fn qualify(namespace: &str, label: &str) -> String {
+    if label.contains(NAMESPACE_SEPARATOR) {
+        label.to_string()
+    } else {
+        format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, label)
+    }
+}
+
+/// The namespace of a qualified label (up to its last `::`), if any.
+pub fn namespace_of(label: &str) -> Option<&str> {
+    label.rfind(NAMESPACE_SEPARATOR).map(|end| &label[..end])
+}
+
+/// How labels are compared when they are resolved to ids.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Normalizer {
+    /// Byte-for-byte: `"KeyCard"` and `"keycard"` are two flags.
+    #[default]
+    Exact,
+    /// Surrounding whitespace and letter case are ignored:
+    /// `" KeyCard "`, `"keycard"` and `"KeyCard"` are one flag.
+    CaseInsensitiveTrim,
+}
+
+impl Normalizer {
+    /// The lookup key of `label`.
+    pub fn key<'a>(&self, label: &'a str) -> Cow<'a, str> {
+        match self {
+            Normalizer::Exact => Cow::Borrowed(label),
+            Normalizer::CaseInsensitiveTrim => Cow::Owned(label.trim().to_lowercase()),
+        }
+    }
+}
+
+/// Interns labels. Ids start at 1 and are never reused.
+/// A label keeps the spelling it was first seen with; aliases resolve to
+/// an existing id but are never displayed.
+#[derive(Clone, Debug)]
+pub struct SymbolTable {
+    label_to_id: HashMap<String, FlagId>, // Keyed by normalized label
+    id_to_label: HashMap<FlagId, String>,
+    next_id: FlagId,
+    normalizer: Normalizer,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::with_normalizer(Normalizer::Exact)
+    }
+
+    pub fn with_normalizer(normalizer: Normalizer) -> Self {
+        Self {
+            label_to_id: HashMap::new(),
+            id_to_label: HashMap::new(),
+            next_id: 1,
+            normalizer,
+        }
+    }
+
+    pub fn normalizer(&self) -> Normalizer {
+        self.normalizer
+    }
+
+    /// Get ID for a label, creating it if necessary.
+    pub fn id(&mut self, label: &str) -> FlagId {
+        let key = self.normalizer.key(label);
+        if let Some(&id) = self.label_to_id.get(key.as_ref()) {
+            id
+        } else {
+            let id = self.next_id;
+            self.next_id += 1;
+            self.label_to_id.insert(key.into_owned(), id);
+            self.id_to_label.insert(id, label.to_string());
+            id
+        }
+    }
+
+    /// ID of `label` inside `namespace` (see `qualify`), creating it if
+    /// necessary. `id_in("kitchen", "Ready")` and `id("kitchen::Ready")` are
+    /// the same flag; `id("Ready")` is another one.
+    pub fn id_in(&mut self, namespace: &str, label: &str) -> FlagId {
+        self.id(&qualify(namespace, label))
+    }
+
+    /// Make `synonym` resolve to the flag of `canonical` (interned if new).
+    /// A synonym that is already an alias is re-pointed. Returns `false`,
+    /// changing nothing, if `synonym` is the name of another flag: that
+    /// would silently merge two flags.
+    pub fn alias(&mut self, canonical: &str, synonym: &str) -> bool {
+        let target = self.id(canonical);
+        let key = self.normalizer.key(synonym).into_owned();
+        if let Some(&existing) = self.label_to_id.get(&key) {
+            let own_name = self
+                .id_to_label
+                .get(&existing)
+                .is_some_and(|label| self.normalizer.key(label) == key.as_str());
+            if existing != target && own_name {
+                return false;
+            }
+        }
+        self.label_to_id.insert(key, target);
+        true
+    }
+
+    /// ID of an already known label.
+    pub fn get(&self, label: &str) -> Option<FlagId> {
+        self.label_to_id
+            .get(self.normalizer.key(label).as_ref())
+            .copied()
+    }
+
+    pub fn label(&self, id: FlagId) -> String {
+        self.id_to_label
+            .get(&id)
+            .cloned()
+            .unwrap_or(format!("?{}", id))
+    }
+
+    /// `label` without the copy; `None` for an unknown id.
+    pub fn label_str(&self, id: FlagId) -> Option<&str> {
+        self.id_to_label.get(&id).map(String::as_str)
+    }
+
+    /// How many labels are interned; ids run from 1 to `len`.
+    pub fn len(&self) -> usize {
+        self.id_to_label.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.id_to_label.is_empty()
+    }
+
+    /// Every known id, ascending.
+    pub fn ids(&self) -> Vec<FlagId> {
+        let mut ids: Vec<FlagId> = self.id_to_label.keys().copied().collect();
+        ids.sort();
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Mind;
+    use crate::report::NullReporter;
+
+    #[test]
+    fn case_insensitive_labels_share_a_flag() {
+        let mut symbols = SymbolTable::with_normalizer(Normalizer::CaseInsensitiveTrim);
+        let id = symbols.id("KeyCard");
+        assert_eq!(symbols.id(" keycard "), id);
+        assert_eq!(symbols.get("KEYCARD"), Some(id));
+        assert_eq!(symbols.label(id), "KeyCard");
+
+        let mut exact = SymbolTable::new();
+        assert_ne!(exact.id("KeyCard"), exact.id("keycard"));
+    }
+
+    #[test]
+    fn a_normalized_input_satisfies_the_rule() {
+        let mut mind: Mind = Mind::with_normalizer(Normalizer::CaseInsensitiveTrim);
+        mind.set_reporter(NullReporter);
+        mind.learn(&["KeyCard", "Door"], "Open");
+        mind.inject(&["keycard", " door"]);
+        mind.ponder();
+        assert!(mind.is_active("open"));
+        assert_eq!(mind.active_labels(), ["Door", "KeyCard", "Open"]);
+    }
+
+    #[test]
+    fn modules_merged_into_namespaces_keep_their_own_ready() {
+        let module = |trigger: &str| {
+            let mut mind: Mind = Mind::new();
+            mind.learn(&[trigger], "Ready");
+            mind
+        };
+        let mut mind: Mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind.merge_in("kitchen", &module("Stove"));
+        mind.merge_in("safety", &module("Guard"));
+
+        let kitchen = mind.symbols.get("kitchen::Ready").unwrap();
+        let safety = mind.symbols.get("safety::Ready").unwrap();
+        assert_ne!(kitchen, safety);
+        assert_eq!(mind.symbols.get("Ready"), None);
+
+        mind.inject(&["kitchen::Stove"]);
+        mind.ponder();
+        assert!(mind.is_active("kitchen::Ready"));
+        assert!(!mind.is_active("safety::Ready"));
+    }
+}
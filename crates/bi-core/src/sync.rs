@@ -0,0 +1,87 @@
+//! Per-tick change sets for mirroring the active memory remotely.
+
+use alloc::vec::Vec;
+
+use crate::collections::BTreeSet;
+use crate::engine::Mind;
+use crate::symbols::FlagId;
+
+/// Wire-efficient change set of the active memory for mirroring it remotely.
+/// Apply diffs on top of an initial full copy of the active flags.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TickDiff {
+    pub tick: usize, // The mind's tick count since `new` / `reset_memory`; 0 outside a tick
+    pub added: Vec<FlagId>, // Sorted
+    pub removed: Vec<FlagId>, // Sorted
+}
+
+impl<T> Mind<T> {
+    pub(crate) fn note_added(&mut self, id: FlagId) {
+        self.touch(id);
+        if !self.pending_removed.remove(&id) {
+            self.pending_added.insert(id);
+        }
+    }
+
+    pub(crate) fn note_removed(&mut self, id: FlagId) {
+        self.touch(id);
+        if !self.pending_added.remove(&id) {
+            self.pending_removed.insert(id);
+        }
+    }
+
+    /// Close the pending change set into `last_diff`, if anything changed,
+    /// and fold it into the diff `take_diff` hands out next.
+    pub(crate) fn flush_diff(&mut self, tick: usize) {
+        if self.pending_added.is_empty() && self.pending_removed.is_empty() {
+            return;
+        }
+        self.last_diff = TickDiff {
+            tick,
+            added: core::mem::take(&mut self.pending_added)
+                .into_iter()
+                .collect(),
+            removed: core::mem::take(&mut self.pending_removed)
+                .into_iter()
+                .collect(),
+        };
+
+        // A flag that came and went (or went and came) since the last read
+        // is no change to a mirror.
+        let unread = &mut self.unread_diff;
+        let mut added: BTreeSet<FlagId> = unread.added.drain(..).collect();
+        let mut removed: BTreeSet<FlagId> = unread.removed.drain(..).collect();
+        for id in &self.last_diff.added {
+            if !removed.remove(id) {
+                added.insert(*id);
+            }
+        }
+        for id in &self.last_diff.removed {
+            if !added.remove(id) {
+                removed.insert(*id);
+            }
+        }
+        *unread = TickDiff {
+            tick,
+            added: added.into_iter().collect(),
+            removed: removed.into_iter().collect(),
+        };
+    }
+
+    /// Flags that changed in the most recent tick that changed anything,
+    /// including injections made before it. `retract` recomputes memory
+    /// outside the tick loop, so its diff carries tick 0. A `ponder` runs
+    /// several ticks and only the last one's changes are left here: read
+    /// after every `tick`, or use `take_diff`.
+    pub fn tick_diff(&self) -> TickDiff {
+        self.last_diff.clone()
+    }
+
+    /// Every change since the previous `take_diff` (or since `new` /
+    /// `reset_memory`) as one diff, stamped with the latest tick it covers;
+    /// empty if nothing changed. Applying each taken diff in turn keeps a
+    /// mirror exact however many ticks ran in between.
+    pub fn take_diff(&mut self) -> TickDiff {
+        core::mem::take(&mut self.unread_diff)
+    }
+}
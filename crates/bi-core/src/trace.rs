@@ -0,0 +1,897 @@
+//! Explainability (White Box Debugging): derivation trees and their renderings.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt;
+
+#[cfg(feature = "std")]
+use ptree::{TreeBuilder, print_tree};
+
+use crate::collections::{BTreeSet, HashMap, HashSet};
+use crate::engine::{Mind, Source};
+#[cfg(feature = "std")]
+use crate::error::MindError;
+use crate::rules::Rule;
+use crate::symbols::FlagId;
+
+/// A derivation tree detached from any mind.
+/// Labels replace ids so explanations from different runs can be compared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Explanation {
+    pub label: String,
+    pub kind: NodeKind,
+    pub causes: Vec<Explanation>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeKind {
+    Input,
+    Derived,
+    Missing,
+    Cycle, // Already on the current path, not expanded again
+}
+
+/// One derivation in two renderings: the technical tree for engineers and
+/// a prose summary for stakeholders. The summary is written from the tree,
+/// so both always describe the same path.
+#[derive(Clone, Debug)]
+pub struct DualExplanation {
+    pub tree: Explanation,
+    pub summary: String,
+}
+
+/// Why a flag is not active (see `explain_absence`), in label form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbsenceReport {
+    pub target: String,
+    pub active: bool,            // It is active after all; nothing to explain
+    pub rules: Vec<RuleFailure>, // One per rule deriving the target; none if no rule does
+}
+
+/// What keeps one rule from deriving its output against current memory.
+/// All empty: the rule holds, and the next `ponder` derives the output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleFailure {
+    pub rule: usize,                    // Index into `rules`
+    pub missing: Vec<String>,           // Triggers that are not active
+    pub group: Option<(usize, usize)>,  // (needed, active) of an unmet group; exclusive needs 1
+    pub blocking: Vec<String>,          // Active forbids; for a strict rule, any not known false
+    pub too_weak: Option<f32>,          // Fuzzy value under the rule's or the mind's bar
+    pub disabled_group: Option<String>, // Its rule group, switched off
+}
+
+impl<T> Mind<T> {
+    /// Why `target` is not active: for every rule that outputs it, the
+    /// triggers still missing (a group's gate among them), the group that is short, the forbids in the
+    /// way, for fuzzy minds a value too weak to fire, and a rule group that
+    /// is switched off. Checked against memory as it is now; votes are not
+    /// covered.
+    pub fn explain_absence(&self, target: &str) -> AbsenceReport {
+        let id = self.symbols.get(target);
+        let active = id.is_some_and(|id| self.active_memory.contains_key(&id));
+        let mut report = AbsenceReport {
+            target: target.to_string(),
+            active,
+            rules: Vec::new(),
+        };
+        let Some(id) = id.filter(|_| !active) else {
+            return report;
+        };
+
+        let memory = self.active_ids();
+        let labels = |ids: Vec<FlagId>| ids.into_iter().map(|id| self.label(id)).collect();
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.output != id {
+                continue;
+            }
+            let missing = rule
+                .triggers
+                .iter()
+                .copied()
+                .chain(self.gate(rule))
+                .filter(|t| !memory.contains(t))
+                .collect();
+            let present = rule.any_of.iter().filter(|a| memory.contains(a)).count();
+            let group = match (rule.any_of.is_empty(), rule.exclusive) {
+                (true, _) => None,
+                (false, true) => (present != 1).then_some((1, present)),
+                (false, false) => (present < rule.quorum).then_some((rule.quorum, present)),
+            };
+            let blocking = rule
+                .forbids
+                .iter()
+                .copied()
+                .filter(|f| memory.contains(f) || (rule.strict && !self.known_false.contains(f)))
+                .collect();
+            let mut failure = RuleFailure {
+                rule: index,
+                missing: labels(missing),
+                group,
+                blocking: labels(blocking),
+                too_weak: None,
+                disabled_group: rule
+                    .group
+                    .filter(|_| !self.switched_on(rule))
+                    .map(|group| self.group_names[group].clone()),
+            };
+            if failure.missing.is_empty() && failure.group.is_none() && failure.blocking.is_empty()
+            {
+                let value = self.fuzzy_value(rule, &self.causes(rule, &memory), &memory);
+                if !self.clears_threshold(rule.output, value) || value < rule.min_confidence {
+                    failure.too_weak = Some(value);
+                }
+            }
+            report.rules.push(failure);
+        }
+        report
+    }
+
+    /// Visualizes the logic chain for a specific concept.
+    /// Fails only if the tree cannot be written to stdout.
+    #[cfg(feature = "std")]
+    pub fn trace(&self, target: &str) -> Result<(), MindError> {
+        self.trace_depth(target, usize::MAX)
+    }
+
+    /// `trace`, down to `max_depth` levels of causes below `target`.
+    /// A derived flag at the cut is shown as `` `X` (...) ``; inputs there
+    /// keep their `(Input)` mark, having nothing below them anyway.
+    #[cfg(feature = "std")]
+    pub fn trace_depth(&self, target: &str, max_depth: usize) -> Result<(), MindError> {
+        println!("\n=== Trace: `{}` ===", target);
+        self.print_tree_of(target, max_depth)
+    }
+
+    /// `trace` for several conclusions at once: one header, then one tree
+    /// per target in the given order, rendered exactly like `trace`.
+    #[cfg(feature = "std")]
+    pub fn trace_all(&self, targets: &[&str]) -> Result<(), MindError> {
+        let names: Vec<String> = targets.iter().map(|t| format!("`{}`", t)).collect();
+        println!("\n=== Trace: {} ===", names.join(", "));
+        for target in targets {
+            self.print_tree_of(target, usize::MAX)?;
+        }
+        Ok(())
+    }
+
+    /// The body of `trace_depth`: the tree, or why there is none.
+    #[cfg(feature = "std")]
+    fn print_tree_of(&self, target: &str, max_depth: usize) -> Result<(), MindError> {
+        if let Some(id) = self.symbols.get(target) {
+            if self.active_memory.contains_key(&id) {
+                let mut builder = TreeBuilder::new(self.node_text_within(id, max_depth));
+                self.build_tree_recursive(id, &mut builder, &mut vec![id], max_depth);
+                print_tree(&builder.build()).map_err(|err| MindError::Render(err.to_string()))?;
+            } else if let Some(blockers) = self.blocked.get(&id) {
+                println!("{}", self.blocked_text(target, blockers));
+            } else {
+                println!("Memory does not contain `{}`", target);
+            }
+        } else {
+            println!("Unknown concept: `{}`", target);
+        }
+        Ok(())
+    }
+
+    /// "`X` was NOT derived because `A` is active and `B` is not known false"
+    #[cfg(feature = "std")]
+    fn blocked_text(&self, target: &str, blockers: &[FlagId]) -> String {
+        let reasons = blockers
+            .iter()
+            .map(|id| {
+                let state = if self.active_memory.contains_key(id) {
+                    "is active"
+                } else {
+                    "is not known false"
+                };
+                format!("`{}` {}", self.label(*id), state)
+            })
+            .collect::<Vec<_>>()
+            .join(" and ");
+        format!("`{}` was NOT derived because {}", target, reasons)
+    }
+
+    /// Verbose traces: derived flags show the weight of the rule behind
+    /// their leading justification, e.g. `` `Outing` (w=0.90) ``.
+    pub fn set_trace_weights(&mut self, verbose: bool) {
+        self.trace_weights = verbose;
+    }
+
+    /// Timed traces: derived flags show the tick that derived them (see
+    /// `derived_at`), e.g. `` `Steam` @t2 ``.
+    pub fn set_trace_ticks(&mut self, timed: bool) {
+        self.trace_ticks = timed;
+    }
+
+    fn node_text(&self, id: FlagId) -> String {
+        let label = self.label(id);
+        match self.active_memory.get(&id) {
+            Some(Source::Input) => format!("`{}` (Input)", label),
+            Some(source @ Source::Derived { .. }) => {
+                let causes = source.causes();
+                let text = match self.deriving_rule(id, causes) {
+                    Some(rule) if self.trace_weights => {
+                        format!("`{}` (w={:.2})", label, rule.weight)
+                    }
+                    None if self.custom_derivation(id, causes) => format!("`{}` (custom)", label),
+                    _ => format!("`{}`", label), // Or won a vote
+                };
+                match self.activated_at.get(&id) {
+                    Some(tick) if self.trace_ticks => format!("{} @t{}", text, tick),
+                    _ => text,
+                }
+            }
+            None => format!("`{}` (MISSING)", label),
+        }
+    }
+
+    /// The first rule outputting `id` that `causes` fit: all its triggers,
+    /// and a member of its group if it has one.
+    fn deriving_rule(&self, id: FlagId, causes: &[FlagId]) -> Option<&Rule> {
+        self.deriving_rule_index(id, causes)
+            .map(|index| &self.rules[index])
+    }
+
+    /// Index of `deriving_rule`.
+    pub(crate) fn deriving_rule_index(&self, id: FlagId, causes: &[FlagId]) -> Option<usize> {
+        self.rules.iter().position(|rule| {
+            rule.output == id
+                && (rule.any_of.is_empty() || rule.any_of.iter().any(|a| causes.contains(a)))
+                && rule.triggers.iter().all(|t| causes.contains(t))
+        })
+    }
+
+    /// `node_text`, with `(...)` for a derived flag whose causes are cut off.
+    #[cfg(feature = "std")]
+    fn node_text_within(&self, id: FlagId, depth: usize) -> String {
+        match self.active_memory.get(&id) {
+            Some(Source::Derived { .. }) if depth == 0 => format!("`{}` (...)", self.label(id)),
+            _ => self.node_text(id),
+        }
+    }
+
+    /// `path` holds the flags from the root down to `id`; a cause already
+    /// on it is rendered as a `(cycle)` leaf instead of being expanded again.
+    /// A flag with several justifications gets one `(alternative n)` branch each.
+    /// `depth` levels of causes are shown below `id` (alternatives are no level).
+    #[cfg(feature = "std")]
+    fn build_tree_recursive(
+        &self,
+        id: FlagId,
+        builder: &mut TreeBuilder,
+        path: &mut Vec<FlagId>,
+        depth: usize,
+    ) {
+        let Some(Source::Derived { justifications }) = self.active_memory.get(&id) else {
+            return;
+        };
+        if depth == 0 {
+            return;
+        }
+        if let [causes] = justifications.as_slice() {
+            self.build_causes(causes, builder, path, depth);
+            return;
+        }
+        for (n, causes) in justifications.iter().enumerate() {
+            builder.begin_child(format!("(alternative {})", n + 1));
+            self.build_causes(causes, builder, path, depth);
+            builder.end_child();
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn build_causes(
+        &self,
+        causes: &[FlagId],
+        builder: &mut TreeBuilder,
+        path: &mut Vec<FlagId>,
+        depth: usize,
+    ) {
+        for &cause_id in causes {
+            if path.contains(&cause_id) {
+                builder.add_empty_child(format!("`{}` (cycle)", self.label(cause_id)));
+                continue;
+            }
+            builder.begin_child(self.node_text_within(cause_id, depth - 1));
+            path.push(cause_id);
+            self.build_tree_recursive(cause_id, builder, path, depth - 1);
+            path.pop();
+            builder.end_child();
+        }
+    }
+
+    /// The tree `trace` prints, as plain text for log files and fixtures:
+    /// one `- ` bullet per node, indented two spaces per level, with the
+    /// same `(Input)`, `(MISSING)`, `(cycle)` and `(alternative n)` marks
+    /// and no colors. `None` if the concept is unknown or not active.
+    pub fn trace_text(&self, target: &str) -> Option<String> {
+        let id = self.symbols.get(target)?;
+        self.active_memory.get(&id)?;
+        let mut out = format!("- {}\n", self.node_text(id));
+        self.text_recursive(id, &mut out, &mut vec![id], 1);
+        Some(out)
+    }
+
+    /// `build_tree_recursive` for `trace_text`, at `indent` levels.
+    fn text_recursive(&self, id: FlagId, out: &mut String, path: &mut Vec<FlagId>, indent: usize) {
+        let Some(Source::Derived { justifications }) = self.active_memory.get(&id) else {
+            return;
+        };
+        if let [causes] = justifications.as_slice() {
+            self.text_causes(causes, out, path, indent);
+            return;
+        }
+        for (n, causes) in justifications.iter().enumerate() {
+            text_line(out, indent, &format!("(alternative {})", n + 1));
+            self.text_causes(causes, out, path, indent + 1);
+        }
+    }
+
+    fn text_causes(
+        &self,
+        causes: &[FlagId],
+        out: &mut String,
+        path: &mut Vec<FlagId>,
+        indent: usize,
+    ) {
+        for &cause_id in causes {
+            if path.contains(&cause_id) {
+                text_line(out, indent, &format!("`{}` (cycle)", self.label(cause_id)));
+                continue;
+            }
+            text_line(out, indent, &self.node_text(cause_id));
+            path.push(cause_id);
+            self.text_recursive(cause_id, out, path, indent + 1);
+            path.pop();
+        }
+    }
+
+    /// Executive summary: every top-level conclusion (a derived flag that is
+    /// not itself a cause of anything active), one line each: its
+    /// explanation in `summarize` form, down to the inputs it rests on.
+    pub fn decision_report(&self) -> String {
+        let used_as_cause: HashSet<FlagId> = self
+            .active_memory
+            .values()
+            .filter_map(|source| match source {
+                Source::Derived { justifications } => {
+                    Some(justifications.iter().flatten().copied())
+                }
+                Source::Input => None,
+            })
+            .flatten()
+            .collect();
+
+        let mut conclusions: Vec<String> = self
+            .active_memory
+            .iter()
+            .filter(|(id, _)| !used_as_cause.contains(*id))
+            .filter(|(_, source)| matches!(source, Source::Derived { .. }))
+            .map(|(id, _)| self.label(*id))
+            .collect();
+        conclusions.sort();
+
+        let mut report = String::new();
+        for tree in conclusions.iter().filter_map(|label| self.explain(label)) {
+            report.push_str(&format!("- {}\n", summarize(&tree)));
+        }
+        report
+    }
+
+    /// Graphviz DOT of the derivation graph, edges pointing from cause to
+    /// effect: the part reachable from `target` through recorded causes, or
+    /// the whole active memory for `None`. Inputs are boxes, derived flags
+    /// ellipses, and missing (unknown or inactive) flags dashed, so broken
+    /// graphs stay visible. Render with `dot -Tpng`.
+    pub fn to_dot(&self, target: Option<&str>) -> String {
+        let mut out = String::from("digraph derivation {\n    rankdir=LR;\n");
+        let mut missing_target = None;
+
+        let mut nodes: BTreeSet<FlagId> = BTreeSet::new();
+        match target {
+            None => nodes.extend(self.active_memory.keys()),
+            Some(label) => match self.symbols.get(label) {
+                Some(id) => nodes = self.derivation_nodes(id),
+                None => missing_target = Some(label),
+            },
+        }
+
+        if let Some(label) = missing_target {
+            out.push_str(&format!(
+                "    unknown [label={}, style=dashed];\n",
+                dot_string(label)
+            ));
+        }
+        for &id in &nodes {
+            let style = match self.active_memory.get(&id) {
+                Some(Source::Input) => "shape=box",
+                Some(Source::Derived { .. }) => "shape=ellipse",
+                None => "shape=ellipse, style=dashed",
+            };
+            out.push_str(&format!(
+                "    f{} [label={}, {}];\n",
+                id,
+                dot_string(&self.label(id)),
+                style
+            ));
+        }
+        for &id in &nodes {
+            if let Some(Source::Derived { justifications }) = self.active_memory.get(&id) {
+                let causes: BTreeSet<FlagId> = justifications.iter().flatten().copied().collect();
+                for cause in causes {
+                    out.push_str(&format!("    f{} -> f{};\n", cause, id));
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid flowchart (`graph TD`) of the derivation of `target`, edges
+    /// pointing from cause to effect, to paste into a Markdown `mermaid`
+    /// code block. Covers what `to_dot` draws for `target`:
+    /// every recorded justification, each flag declared once even on
+    /// cycles. Inputs are green boxes, derived flags rounded, missing flags
+    /// dashed. `None` if the concept is unknown or not active.
+    pub fn trace_mermaid(&self, target: &str) -> Option<String> {
+        let id = self.symbols.get(target)?;
+        self.active_memory.get(&id)?;
+        let nodes = self.derivation_nodes(id);
+
+        let mut out = String::from("graph TD\n");
+        for &id in &nodes {
+            let label = mermaid_string(&self.label(id));
+            let node = match self.active_memory.get(&id) {
+                Some(Source::Input) => format!("f{}[{}]:::input", id, label),
+                Some(Source::Derived { .. }) => format!("f{}({})", id, label),
+                None => format!("f{}({}):::missing", id, label),
+            };
+            out.push_str(&format!("    {}\n", node));
+        }
+        for &id in &nodes {
+            if let Some(Source::Derived { justifications }) = self.active_memory.get(&id) {
+                let causes: BTreeSet<FlagId> = justifications.iter().flatten().copied().collect();
+                for cause in causes {
+                    out.push_str(&format!("    f{} --> f{}\n", cause, id));
+                }
+            }
+        }
+        out.push_str("    classDef input fill:#e6f4ea,stroke:#2e7d32\n");
+        out.push_str("    classDef missing stroke-dasharray:4 4\n");
+        Some(out)
+    }
+
+    /// `id` and every flag its recorded justifications reach, transitively.
+    fn derivation_nodes(&self, id: FlagId) -> BTreeSet<FlagId> {
+        let mut nodes = BTreeSet::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if nodes.insert(id)
+                && let Some(Source::Derived { justifications }) = self.active_memory.get(&id)
+            {
+                stack.extend(justifications.iter().flatten());
+            }
+        }
+        nodes
+    }
+
+    /// The recorded derivation of `target` as a detached tree,
+    /// following the leading justification of each flag.
+    /// `None` if the concept is unknown or not active.
+    pub fn explain(&self, target: &str) -> Option<Explanation> {
+        let id = self.symbols.get(target)?;
+        self.active_memory.get(&id)?;
+        Some(self.explain_recursive(id, &mut Vec::new()))
+    }
+
+    /// The trace of `target` as structured data:
+    /// `{ "label": ..., "kind": "input" | "derived" | "missing" | "cycle", "causes": [...] }`,
+    /// nested like the printed tree. `None` if the concept is unknown or not active.
+    pub fn trace_json(&self, target: &str) -> Option<serde_json::Value> {
+        self.explain(target).map(|tree| tree.to_json())
+    }
+
+    /// `trace_json` for several targets as one forest, every flag emitted
+    /// once however many proofs share it:
+    /// `{ "roots": [label, ...], "nodes": { label: { "kind": ..., "causes": [label, ...] } } }`.
+    /// Causes refer to other entries of `nodes` by label; each derived flag
+    /// lists its leading justification, as in `trace_json`, so cycles need
+    /// no marking. Targets that are unknown or not active are `missing` nodes.
+    pub fn trace_json_all(&self, targets: &[&str]) -> serde_json::Value {
+        let mut nodes = serde_json::Map::new();
+        let mut stack: Vec<(String, Option<FlagId>)> = targets
+            .iter()
+            .map(|target| (target.to_string(), self.symbols.get(target)))
+            .collect();
+        while let Some((label, id)) = stack.pop() {
+            if nodes.contains_key(&label) {
+                continue;
+            }
+            let (kind, causes) = match id.and_then(|id| self.active_memory.get(&id)) {
+                Some(Source::Input) => ("input", Vec::new()),
+                Some(source @ Source::Derived { .. }) => ("derived", source.causes().to_vec()),
+                None => ("missing", Vec::new()),
+            };
+            let cause_labels: Vec<String> = causes.iter().map(|id| self.label(*id)).collect();
+            stack.extend(
+                cause_labels
+                    .iter()
+                    .cloned()
+                    .zip(causes.into_iter().map(Some)),
+            );
+            nodes.insert(
+                label,
+                serde_json::json!({ "kind": kind, "causes": cause_labels }),
+            );
+        }
+        serde_json::json!({ "roots": targets, "nodes": nodes })
+    }
+
+    /// Input axioms of the shallowest derivation of `target`: the one needing
+    /// the fewest ticks, searched breadth-first over every justification in
+    /// memory (the recorded causes, plus any rule that currently holds).
+    /// Ties between justifications go to the lexicographically smallest
+    /// cause labels. Sorted by id; `None` if the target is not active.
+    pub fn explain_shortest(&self, target: &str) -> Option<Vec<FlagId>> {
+        let target = self.symbols.get(target)?;
+        self.active_memory.get(&target)?;
+        let active = self.active_ids();
+
+        let mut justifications: HashMap<FlagId, Vec<Vec<FlagId>>> = HashMap::new();
+        for (id, source) in &self.active_memory {
+            if let Source::Derived {
+                justifications: recorded,
+            } = source
+            {
+                justifications.insert(*id, recorded.clone());
+            }
+        }
+        for rule in &self.rules {
+            if let Some(known) = justifications.get_mut(&rule.output)
+                && self.satisfied(rule, &active)
+            {
+                known.push(self.causes(rule, &active));
+            }
+        }
+
+        // Level by level: a flag joins as soon as one justification rests
+        // entirely on the levels before it.
+        let mut reached = self.input_ids();
+        let mut chosen: HashMap<FlagId, Vec<FlagId>> = HashMap::new();
+        let mut pending: Vec<FlagId> = justifications.keys().copied().collect();
+        pending.sort();
+        loop {
+            let mut level = Vec::new();
+            pending.retain(|id| {
+                let best = justifications[id]
+                    .iter()
+                    .filter(|causes| causes.iter().all(|c| reached.contains(c)))
+                    .min_by_key(|causes| {
+                        let mut labels: Vec<String> =
+                            causes.iter().map(|c| self.label(*c)).collect();
+                        labels.sort();
+                        labels
+                    });
+                match best {
+                    Some(causes) => {
+                        level.push((*id, causes.clone()));
+                        false
+                    }
+                    None => true,
+                }
+            });
+            if level.is_empty() {
+                break;
+            }
+            for (id, causes) in level {
+                reached.insert(id);
+                chosen.insert(id, causes);
+            }
+        }
+
+        let mut inputs = BTreeSet::new();
+        let mut stack = vec![target];
+        while let Some(id) = stack.pop() {
+            match chosen.get(&id) {
+                Some(causes) => stack.extend(causes),
+                None if reached.contains(&id) => {
+                    inputs.insert(id);
+                }
+                None => return None, // Circular support only
+            }
+        }
+        Some(inputs.into_iter().collect())
+    }
+
+    /// `explain` plus a prose summary of that very tree.
+    pub fn explain_dual(&self, target: &str) -> Option<DualExplanation> {
+        let tree = self.explain(target)?;
+        let summary = summarize(&tree);
+        Some(DualExplanation { tree, summary })
+    }
+
+    fn explain_recursive(&self, id: FlagId, path: &mut Vec<FlagId>) -> Explanation {
+        let label = self.label(id);
+        let (kind, causes) = match self.active_memory.get(&id) {
+            Some(Source::Input) => (NodeKind::Input, Vec::new()),
+            None => (NodeKind::Missing, Vec::new()),
+            Some(Source::Derived { .. }) if path.contains(&id) => (NodeKind::Cycle, Vec::new()),
+            Some(source @ Source::Derived { .. }) => {
+                path.push(id);
+                let causes = source
+                    .causes()
+                    .iter()
+                    .map(|&cause| self.explain_recursive(cause, path))
+                    .collect();
+                path.pop();
+                (NodeKind::Derived, causes)
+            }
+        };
+        Explanation {
+            label,
+            kind,
+            causes,
+        }
+    }
+}
+
+/// `` `X` is not active: `` and one line per rule, e.g.
+/// `` rule #2: missing `Cut`; blocked by `Frozen` ``.
+impl fmt::Display for AbsenceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.active {
+            return write!(f, "`{}` is active", self.target);
+        }
+        if self.rules.is_empty() {
+            return write!(f, "`{}` is not active: no rule derives it", self.target);
+        }
+        let quoted = |labels: &[String]| {
+            labels
+                .iter()
+                .map(|l| format!("`{}`", l))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(f, "`{}` is not active:", self.target)?;
+        for failure in &self.rules {
+            let mut reasons = Vec::new();
+            if !failure.missing.is_empty() {
+                reasons.push(format!("missing {}", quoted(&failure.missing)));
+            }
+            if let Some((needed, active)) = failure.group {
+                reasons.push(format!("group needs {}, has {}", needed, active));
+            }
+            if !failure.blocking.is_empty() {
+                reasons.push(format!("blocked by {}", quoted(&failure.blocking)));
+            }
+            if let Some(value) = failure.too_weak {
+                reasons.push(format!("too weak ({:.2})", value));
+            }
+            if let Some(group) = &failure.disabled_group {
+                reasons.push(format!("rule group `{}` disabled", group));
+            }
+            if reasons.is_empty() {
+                reasons.push("holds now, not pondered yet".to_string());
+            }
+            write!(f, "\n  rule #{}: {}", failure.rule, reasons.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Explanation {
+    /// JSON form used by `trace_json`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let kind = match self.kind {
+            NodeKind::Input => "input",
+            NodeKind::Derived => "derived",
+            NodeKind::Missing => "missing",
+            NodeKind::Cycle => "cycle",
+        };
+        serde_json::json!({
+            "label": self.label,
+            "kind": kind,
+            "causes": self.causes.iter().map(Explanation::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// A DOT string literal: quoted, with `"` and `\\` escaped.
+pub(crate) fn dot_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// One `trace_text` bullet, `indent` levels deep.
+fn text_line(out: &mut String, indent: usize, text: &str) {
+    out.push_str(&format!("{}- {}\n", "  ".repeat(indent), text));
+}
+
+/// A quoted Mermaid node label; `"` becomes the `#quot;` entity.
+fn mermaid_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;"))
+}
+
+// ============================================================================
+// Explanation Rendering
+// ============================================================================
+
+/// Plain-language walk through a tree: one sentence per derived concept
+/// (each stated once, conclusion first), then the facts it was given.
+pub fn summarize(tree: &Explanation) -> String {
+    let mut sentences = Vec::new();
+    let mut givens = Vec::new();
+    let mut stack = vec![tree];
+    while let Some(node) = stack.pop() {
+        match node.kind {
+            NodeKind::Derived => {
+                let causes: Vec<String> = node
+                    .causes
+                    .iter()
+                    .map(|c| format!("`{}`", c.label))
+                    .collect();
+                let causes = match causes.split_last() {
+                    Some((last, rest)) if !rest.is_empty() => {
+                        format!("{} and {}", rest.join(", "), last)
+                    }
+                    _ => causes.concat(),
+                };
+                let sentence = format!("`{}` follows from {}.", node.label, causes);
+                if !sentences.contains(&sentence) {
+                    sentences.push(sentence);
+                }
+                stack.extend(node.causes.iter().rev());
+            }
+            NodeKind::Input if !givens.contains(&node.label) => givens.push(node.label.clone()),
+            _ => {}
+        }
+    }
+
+    givens.sort();
+    let givens = givens
+        .iter()
+        .map(|l| format!("`{}`", l))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{} Given: {}.", sentences.join(" "), givens)
+}
+
+// ============================================================================
+// Explanation Diff
+// ============================================================================
+
+/// Unified-diff style comparison of two explanations.
+/// Unchanged reasoning is indented with two spaces, removed branches are
+/// prefixed `-`, added branches `+`. Causes are matched by label.
+pub fn trace_patch(before: &Explanation, after: &Explanation) -> String {
+    let mut patch = String::new();
+    patch_recursive(before, after, 0, &mut patch);
+    patch
+}
+
+fn patch_recursive(before: &Explanation, after: &Explanation, depth: usize, out: &mut String) {
+    if before.label != after.label || before.kind != after.kind {
+        patch_subtree('-', before, depth, out);
+        patch_subtree('+', after, depth, out);
+        return;
+    }
+
+    patch_line(' ', before, depth, out);
+    for old in &before.causes {
+        if !after.causes.iter().any(|new| new.label == old.label) {
+            patch_subtree('-', old, depth + 1, out);
+        }
+    }
+    for new in &after.causes {
+        match before.causes.iter().find(|old| old.label == new.label) {
+            Some(old) => patch_recursive(old, new, depth + 1, out),
+            None => patch_subtree('+', new, depth + 1, out),
+        }
+    }
+}
+
+fn patch_subtree(mark: char, node: &Explanation, depth: usize, out: &mut String) {
+    patch_line(mark, node, depth, out);
+    for cause in &node.causes {
+        patch_subtree(mark, cause, depth + 1, out);
+    }
+}
+
+fn patch_line(mark: char, node: &Explanation, depth: usize, out: &mut String) {
+    let note = match node.kind {
+        NodeKind::Input => " (Input)",
+        NodeKind::Derived => "",
+        NodeKind::Missing => " (MISSING)",
+        NodeKind::Cycle => " (cycle)",
+    };
+    out.push_str(&format!(
+        "{} {}`{}`{}\n",
+        mark,
+        "  ".repeat(depth),
+        node.label,
+        note
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::NullReporter;
+
+    fn quiet() -> Mind {
+        let mut mind = Mind::new();
+        mind.set_reporter(NullReporter);
+        mind
+    }
+
+    #[test]
+    fn a_circular_proof_ends_in_a_cycle_mark() {
+        let mut mind = quiet();
+        mind.learn(&["Seed"], "A");
+        mind.learn(&["A"], "B");
+        mind.learn(&["B"], "A");
+        mind.inject(&["Seed"]);
+        mind.ponder();
+        let text = mind.trace_text("B").expect("B is active");
+        assert!(text.contains("`B` (cycle)"), "{text}");
+        assert!(text.contains("`Seed` (Input)"), "{text}");
+        // The printed tree takes the same guard
+        #[cfg(feature = "std")]
+        assert_eq!(mind.trace("B"), Ok(()));
+    }
+
+    #[test]
+    fn the_decision_report_summarizes_each_top_level_conclusion() {
+        let mut mind = quiet();
+        mind.learn(&["Dough", "Oven"], "Bread");
+        mind.learn(&["Bread", "Butter"], "Toast");
+        mind.learn(&["Tea"], "Drink");
+        mind.inject(&["Dough", "Oven", "Butter", "Tea"]);
+        mind.ponder();
+        assert_eq!(
+            mind.decision_report(),
+            "- `Drink` follows from `Tea`. Given: `Tea`.\n\
+             - `Toast` follows from `Bread` and `Butter`. \
+             `Bread` follows from `Dough` and `Oven`. Given: `Butter`, `Dough`, `Oven`.\n"
+        );
+    }
+
+    /// The tree `trace_depth` prints, one `depth label` line per node.
+    #[cfg(feature = "std")]
+    fn tree_lines(mind: &Mind, target: &str, max_depth: usize) -> Vec<String> {
+        fn walk(item: &ptree::item::StringItem, depth: usize, out: &mut Vec<String>) {
+            out.push(format!("{} {}", depth, item.text));
+            for child in &item.children {
+                walk(child, depth + 1, out);
+            }
+        }
+        let id = mind.symbols.get(target).expect("known target");
+        let mut builder = TreeBuilder::new(mind.node_text_within(id, max_depth));
+        mind.build_tree_recursive(id, &mut builder, &mut vec![id], max_depth);
+        let mut out = Vec::new();
+        walk(&builder.build(), 0, &mut out);
+        out
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn trace_depth_cuts_a_deep_chain() {
+        let mut mind = quiet();
+        mind.learn(&["In"], "L1");
+        mind.learn(&["L1"], "L2");
+        mind.learn(&["L2"], "L3");
+        mind.learn(&["L3"], "L4");
+        mind.learn(&["L4", "Sensor"], "L5");
+        mind.learn(&["L5"], "L6");
+        mind.inject(&["In", "Sensor"]);
+        mind.ponder();
+
+        assert_eq!(
+            tree_lines(&mind, "L6", 2),
+            ["0 `L6`", "1 `L5`", "2 `L4` (...)", "2 `Sensor` (Input)"]
+        );
+        assert_eq!(tree_lines(&mind, "L6", 0), ["0 `L6` (...)"]);
+        assert_eq!(tree_lines(&mind, "L6", usize::MAX).len(), 8);
+        assert_eq!(mind.trace_depth("L6", 2), Ok(()));
+    }
+}
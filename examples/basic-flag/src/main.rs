@@ -7,159 +7,31 @@
 //! It includes "Sanity Checks" to prove the BI doesn't just react to everything,
 //! but strictly follows the logical constraints.
 
+use bi_core::{LogLayout, Mind, MindError, assert_derives};
 use console::style;
-use std::collections::{HashMap, HashSet};
 
-// ============================================================================
-// Core Definitions
-// ============================================================================
-
-type FlagId = u32;
-
-/// A Logic Link represents a synaptic connection.
-/// It defines what stimulates a result and what inhibits it.
-struct Link {
-    triggers: Vec<FlagId>, // All must be present (AND logic)
-    forbids: Vec<FlagId>,  // Any presence prevents output (NOT logic)
-    output: FlagId,        // The resulting concept
-}
-
-/// The Thinking Engine
-struct Mind {
-    // Symbol Table
-    label_to_id: HashMap<String, FlagId>,
-    id_to_label: HashMap<FlagId, String>,
-    next_id: FlagId,
-
-    // Long-term Memory (Static Rules)
-    links: Vec<Link>,
-
-    // Short-term Memory (Active Context)
-    active_flags: HashSet<FlagId>,
-}
-
-impl Mind {
-    fn new() -> Self {
-        Self {
-            label_to_id: HashMap::new(),
-            id_to_label: HashMap::new(),
-            next_id: 1,
-            links: Vec::new(),
-            active_flags: HashSet::new(),
-        }
-    }
-
-    // --- Knowledge Definition ---
-
-    fn id(&mut self, label: &str) -> FlagId {
-        if let Some(&id) = self.label_to_id.get(label) {
-            id
-        } else {
-            let id = self.next_id;
-            self.next_id += 1;
-            self.label_to_id.insert(label.to_string(), id);
-            self.id_to_label.insert(id, label.to_string());
-            id
-        }
-    }
-
-    fn label(&self, id: FlagId) -> String {
-        self.id_to_label
-            .get(&id)
-            .cloned()
-            .unwrap_or(format!("?{}", id))
-    }
-
-    /// Define a rule: (A + B) - (C) -> D
-    /// Logic: IF (A and B exist) AND (C does NOT exist) THEN D exists.
-    fn rule(&mut self, triggers: &[&str], forbids: &[&str], output: &str) {
-        let t_ids: Vec<FlagId> = triggers.iter().map(|n| self.id(n)).collect();
-        let f_ids: Vec<FlagId> = forbids.iter().map(|n| self.id(n)).collect();
-        let o_id = self.id(output);
-
-        self.links.push(Link {
-            triggers: t_ids,
-            forbids: f_ids,
-            output: o_id,
-        });
-    }
-
-    // --- Runtime ---
-
-    fn reset_memory(&mut self) {
-        self.active_flags.clear();
-    }
-
-    fn inject(&mut self, inputs: &[&str]) {
-        for name in inputs {
-            let id = self.id(name);
-            self.active_flags.insert(id);
-            println!("[Input] + `{}`", style(name).green());
-        }
-    }
-
-    fn tick(&mut self, tick_count: usize) -> bool {
-        let mut new_activations = Vec::new();
-
-        for link in &self.links {
-            // 1. Check Output redundancy
-            if self.active_flags.contains(&link.output) {
-                continue;
-            }
-
-            // 2. Check Triggers (AND Gate)
-            let triggers_met = link.triggers.iter().all(|t| self.active_flags.contains(t));
-            if !triggers_met {
-                continue;
-            }
-
-            // 3. Check Forbids (Inhibition / NOT Gate)
-            let inhibition_active = link.forbids.iter().any(|f| self.active_flags.contains(f));
-            if inhibition_active {
-                continue;
-            }
-
-            // All conditions met
-            new_activations.push((link.output, link.triggers.clone()));
-        }
-
-        if new_activations.is_empty() {
-            return false;
-        }
-
-        // Commit Logic
-        for (out_id, causes) in new_activations {
-            let cause_str = causes
-                .iter()
-                .map(|c| format!("`{}`", self.label(*c)))
-                .collect::<Vec<_>>()
-                .join(", ");
-            let out_str = style(format!("`{}`", self.label(out_id))).yellow().bold();
-
-            println!("[Tick {}] {} ---> {}", tick_count, cause_str, out_str);
-
-            self.active_flags.insert(out_id);
-        }
-
-        true
-    }
-
-    /// Runs until logic stabilizes
-    fn ponder(&mut self) {
-        let mut tick = 1;
-        while self.tick(tick) {
-            tick += 1;
-        }
-        println!(); // Spacer
+/// "`A`, `B`" or "(none)"
+fn label_list(labels: &[String]) -> String {
+    if labels.is_empty() {
+        return "(none)".to_string();
     }
+    labels
+        .iter()
+        .map(|l| format!("`{}`", l))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 // ============================================================================
 // Main Simulation
 // ============================================================================
 
-fn main() {
+fn main() -> Result<(), MindError> {
     let mut mind = Mind::new();
+    mind.set_layout(LogLayout {
+        align_ticks: false,
+        spacer: true,
+    });
 
     println!("=== BI Logic Gate Test ===\n");
 
@@ -176,11 +48,13 @@ fn main() {
     mind.reset_memory();
     mind.inject(&["KeyCard"]); // Missing Fingerprint
     mind.ponder(); // Should produce NOTHING
+    assert_derives!(mind, ["KeyCard"] => [], not ["AccessGranted"]);
 
     println!("Test B: Complete Input (Success Expected)");
     mind.reset_memory();
     mind.inject(&["KeyCard", "Fingerprint"]);
     mind.ponder(); // Should derive AccessGranted
+    assert_derives!(mind, ["KeyCard", "Fingerprint"] => ["AccessGranted"]);
 
     // ---------------------------------------------------------
     // Case 2: OR Gate (Alarm System)
@@ -198,11 +72,13 @@ fn main() {
     mind.reset_memory();
     mind.inject(&["Smoke"]);
     mind.ponder();
+    assert_derives!(mind, ["Smoke"] => ["Alarm"]);
 
     println!("Test B: Path Two");
     mind.reset_memory();
     mind.inject(&["Heat"]);
     mind.ponder();
+    assert_derives!(mind, ["Heat"] => ["Alarm"]);
 
     // ---------------------------------------------------------
     // Case 3: NOT / Inhibition (Smart Light)
@@ -220,9 +96,131 @@ fn main() {
     mind.reset_memory();
     mind.inject(&["SwitchOn", "PowerOutage"]);
     mind.ponder(); // Should NOT turn light on
+    assert_derives!(mind, ["SwitchOn", "PowerOutage"] => [], not ["LightOn"]);
 
     println!("Test B: Normal Operation (Success Expected)");
     mind.reset_memory();
     mind.inject(&["SwitchOn"]);
     mind.ponder(); // Should turn light on
+    assert_derives!(mind, ["SwitchOn"] => ["LightOn"]);
+
+    println!("Test C: Soft Reset (Inputs Kept, Light Re-derived)");
+    mind.reset_derivations();
+    mind.ponder(); // Should turn light on again without re-injecting
+
+    // ---------------------------------------------------------
+    // Case 4: XOR / Mutual Exclusion (Thermostat Mode)
+    // Concept: Exactly one mode may be selected at a time.
+    // ---------------------------------------------------------
+    println!("{}", style("--- Case 4: XOR (Thermostat) ---").bold());
+
+    // Logic: ModeHeat ^ ModeCool -> ModeValid
+    mind.rule_xor(&["ModeHeat", "ModeCool"], "ModeValid");
+
+    println!("Test A: One Mode (Success Expected)");
+    mind.reset_memory();
+    mind.inject(&["ModeHeat"]);
+    mind.ponder();
+    assert_derives!(mind, ["ModeHeat"] => ["ModeValid"]);
+
+    println!("Test B: Both Modes (Failure Expected)");
+    mind.reset_memory();
+    mind.inject(&["ModeHeat", "ModeCool"]);
+    mind.ponder();
+    assert_derives!(mind, ["ModeHeat", "ModeCool"] => [], not ["ModeValid"]);
+
+    // ---------------------------------------------------------
+    // Case 5: Threshold (Evacuation)
+    // Concept: At least K of N signals must agree.
+    // ---------------------------------------------------------
+    println!("{}", style("--- Case 5: Threshold (2 of 3) ---").bold());
+
+    // Logic: any 2 of (Smoke, Heat, GasLeak) -> Evacuate
+    mind.rule_threshold(&["Smoke", "Heat", "GasLeak"], 2, "Evacuate")?;
+
+    println!("Test A: One Signal (Only `Alarm` Expected)");
+    mind.reset_memory();
+    mind.inject(&["Smoke"]);
+    mind.ponder();
+    assert_derives!(mind, ["Smoke"] => ["Alarm"], not ["Evacuate"]);
+
+    println!("Test B: Two Signals (Success Expected)");
+    mind.reset_memory();
+    mind.inject(&["Smoke", "GasLeak"]);
+    mind.ponder();
+    assert_derives!(mind, ["Smoke", "GasLeak"] => ["Alarm", "Evacuate"]);
+
+    // ---------------------------------------------------------
+    // Case 6: Weighted Vote (Winner Takes All)
+    // Concept: Shared evidence, competing conclusions, one winner.
+    // ---------------------------------------------------------
+    println!("\n{}", style("--- Case 6: Weighted Vote ---").bold());
+
+    mind.learn_vote(&[
+        ("Cat", &[("Whiskers", 0.5), ("Meows", 0.9)]),
+        ("Dog", &[("Whiskers", 0.3), ("Barks", 0.9)]),
+    ]);
+
+    mind.reset_memory();
+    mind.inject(&["Whiskers", "Barks"]);
+    mind.ponder(); // Should pick Dog only
+    assert_derives!(mind, ["Whiskers", "Barks"] => ["Dog"], not ["Cat"]);
+
+    // ---------------------------------------------------------
+    // Case 7: Truth Maintenance (Retract With Alternatives)
+    // Concept: A conclusion survives while any rule still supports it.
+    // ---------------------------------------------------------
+    println!("{}", style("--- Case 7: Truth Maintenance ---").bold());
+
+    mind.reset_memory();
+    mind.inject(&["Smoke", "Heat", "GasLeak"]);
+    mind.ponder();
+
+    println!("Test A: Drop `Smoke` (`Alarm` Kept by `Heat`)");
+    let removed = mind.retract(&["Smoke"]);
+    println!("Removed: {}", label_list(&removed));
+
+    println!("Test B: Drop `Heat` (Everything Falls)");
+    let removed = mind.retract(&["Heat"]);
+    println!("Removed: {}", label_list(&removed));
+
+    // ---------------------------------------------------------
+    // Case 8: Outcome Enumeration (Exhaustive Check)
+    // Concept: Try every combination of inputs, list distinct results.
+    // ---------------------------------------------------------
+    println!("\n{}", style("--- Case 8: Outcome Enumeration ---").bold());
+
+    mind.reset_memory();
+    for (inputs, conclusions) in mind.enumerate_outcomes(&["SwitchOn", "PowerOutage"])? {
+        println!("{} => {}", label_list(&inputs), label_list(&conclusions));
+    }
+
+    // ---------------------------------------------------------
+    // Case 9: Custom Gates (Closures)
+    // Concept: Logic no rule shape covers, as a function of presence.
+    // ---------------------------------------------------------
+    println!("\n{}", style("--- Case 9: Custom Gates ---").bold());
+
+    // Logic: StairLight when an odd number of the three switches is up
+    let mut stairs = Mind::new();
+    stairs.set_layout(LogLayout {
+        align_ticks: false,
+        spacer: true,
+    });
+    stairs.rule_custom(&["Hall", "Landing", "Attic"], "StairLight", |up| {
+        up.iter().filter(|on| **on).count() % 2 == 1
+    });
+
+    println!("Test A: Two Switches Up (`StairLight` Stays Off)");
+    stairs.inject(&["Hall", "Landing"]);
+    stairs.ponder();
+    assert_derives!(stairs, ["Hall", "Landing"] => [], not ["StairLight"]);
+
+    println!("Test B: All Three Up (`StairLight` Expected)");
+    stairs.inject(&["Attic"]);
+    stairs.ponder();
+    assert_derives!(stairs, ["Hall", "Landing", "Attic"] => ["StairLight"]);
+    stairs.trace("StairLight")?;
+
+    Ok(())
 }
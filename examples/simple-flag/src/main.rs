@@ -4,246 +4,622 @@
 //! The "Flag" system. It shows how static logical rules combined with dynamic
 //! inputs allow the system to "think" and derive new facts deterministically.
 
-use console::style;
-use ptree::{TreeBuilder, print_tree};
-use std::collections::HashMap;
+use bi_core::prelude::*;
+use bi_core::trace_patch;
+use std::sync::mpsc;
 
-// ============================================================================
-// Type Definitions
-// ============================================================================
+fn main() -> Result<(), MindError> {
+    let mut mind = Mind::new();
 
-type FlagId = u32;
+    // ------------------------------------------------------------------------
+    // Phase 1: Pre-compilation (Hardcoding the Static Logic Graph)
+    // ------------------------------------------------------------------------
+    // In a real scenario, the Compiler generates this from config files.
 
-/// Logic Rule: Defines how Flags connect.
-/// In a real BI, this is stored in the static "LogicRom".
-struct Rule {
-    triggers: Vec<FlagId>, // Conditions (Inputs)
-    output: FlagId,        // Result (Output)
-}
+    // Inheritance: A implies B
+    mind.learn(&["Knife"], "Sharp");
+    mind.learn(&["Apple"], "Fruit");
+    mind.learn(&["Fruit"], "Solid");
 
-/// Source of a Flag's activation.
-/// Essential for "White Box" debugging and logic tracing.
-#[derive(Clone, Debug)]
-enum Source {
-    Input,                           // Axiom injected by user
-    Derived { causes: Vec<FlagId> }, // Reasoned from rules
-}
+    // Interaction: Logic calculation
+    // "If something Sharp cuts something Solid, Separation occurs"
+    mind.learn(&["Sharp", "Solid", "Cut"], "Separation");
 
-/// The simplest BI Runtime Kernel.
-struct Mind {
-    // --- Symbol Table (Human <-> Machine) ---
-    label_to_id: HashMap<String, FlagId>,
-    id_to_label: HashMap<FlagId, String>,
-    next_id: FlagId,
+    // Concept Discovery:
+    // "If a Fruit is Separated, it becomes Fruit Slices"
+    mind.learn(&["Separation", "Fruit"], "Fruit Slices");
 
-    // --- Static Memory (The Brain Structure) ---
-    rules: Vec<Rule>,
+    // ------------------------------------------------------------------------
+    // Phase 2: Runtime (Inference)
+    // ------------------------------------------------------------------------
+    println!("--- Simulation Start ---");
 
-    // --- Dynamic Memory (Consciousness / RAM) ---
-    // Stores the active flags and the reason WHY they are active.
-    active_memory: HashMap<FlagId, Source>,
-}
+    // Scenario: User holds a Knife, an Apple, and performs Cut action.
+    mind.inject(&["Knife", "Apple", "Cut"]);
 
-impl Mind {
-    fn new() -> Self {
-        Self {
-            label_to_id: HashMap::new(),
-            id_to_label: HashMap::new(),
-            next_id: 1,
-            rules: Vec::new(),
-            active_memory: HashMap::new(),
-        }
+    mind.ponder();
+    println!("--- Simulation Stable ---\n");
+
+    // ------------------------------------------------------------------------
+    // Phase 3: Explainability (White Box Check)
+    // ------------------------------------------------------------------------
+    // Prove that the AI knows WHY it thinks "Fruit Slices" exist.
+    mind.trace("Fruit Slices")?;
+
+    // ------------------------------------------------------------------------
+    // Phase 4: Robustness (Single Points of Failure)
+    // ------------------------------------------------------------------------
+    // Conclusions that only one rule supports are brittle: lose the rule, lose the fact.
+    println!("\n=== Single-Support Conclusions ===");
+    for (label, rule) in mind.single_support_conclusions() {
+        println!("`{}` <- rule #{}", label, rule);
     }
 
-    // ========================================================================
-    // Compile-time Helper (Knowledge Construction)
-    // ========================================================================
-
-    /// Get ID for a label, creating it if necessary.
-    fn id(&mut self, label: &str) -> FlagId {
-        if let Some(&id) = self.label_to_id.get(label) {
-            id
-        } else {
-            let id = self.next_id;
-            self.next_id += 1;
-            self.label_to_id.insert(label.to_string(), id);
-            self.id_to_label.insert(id, label.to_string());
-            id
-        }
+    // ------------------------------------------------------------------------
+    // Phase 5: Soft Reset (Re-think After Learning)
+    // ------------------------------------------------------------------------
+    // Derived facts are dropped, but the injected axioms stay in memory.
+    println!("\n--- Soft Reset: Learn `Snack` ---");
+    mind.learn(&["Fruit Slices"], "Snack");
+    mind.reset_derivations();
+    mind.ponder();
+    println!("--- Simulation Stable ---");
+
+    // ------------------------------------------------------------------------
+    // Phase 6: Redundancy (Essential vs Dominated Inputs)
+    // ------------------------------------------------------------------------
+    // A `Blade` makes things `Sharp` too, so the `Knife` is no longer essential.
+    println!("\n=== Redundancy: `Fruit Slices` ===");
+    mind.learn(&["Blade"], "Sharp");
+    mind.inject(&["Blade"]);
+    println!("Supporting: {:?}", mind.supporting_inputs("Fruit Slices"));
+    println!("Dominated:  {:?}", mind.dominated_inputs("Fruit Slices"));
+    println!("Diversity:  {}", mind.explanation_diversity("Fruit Slices"));
+
+    // ------------------------------------------------------------------------
+    // Phase 7: Reasoning Diff (Re-think With the New Knowledge)
+    // ------------------------------------------------------------------------
+    // Losing a cause may leave the same goal standing on a different path.
+    println!("\n--- Drop the `Knife`: Re-think With `Blade` ---");
+    let before = mind.explain("Fruit Slices").expect("derived earlier");
+    mind.retract(&["Knife"]);
+    let after = mind.explain("Fruit Slices").expect("still derived");
+    println!("\n=== Patch: `Fruit Slices` ===");
+    print!("{}", trace_patch(&before, &after));
+    mind.inject(&["Knife"]);
+    mind.ponder();
+    mind.trace("Sharp")?; // Both tools justify it now
+
+    // ------------------------------------------------------------------------
+    // Phase 8: Decision Report (What Was Concluded, and Why)
+    // ------------------------------------------------------------------------
+    println!("\n=== Decision Report ===");
+    print!("{}", mind.decision_report());
+
+    // ------------------------------------------------------------------------
+    // Phase 9: Dual Explanation (Technical Tree + Business Summary)
+    // ------------------------------------------------------------------------
+    let dual = mind.explain_dual("Snack").expect("derived");
+    println!("\n=== Summary: `{}` ===", dual.tree.label);
+    println!("{}", dual.summary);
+
+    // ------------------------------------------------------------------------
+    // Phase 10: Backup Paths (Suggested Redundancy)
+    // ------------------------------------------------------------------------
+    println!("\n=== Backup Paths: `Fruit Slices` ===");
+    for inputs in mind.suggest_redundancy("Fruit Slices", &["Knife", "Blade", "Apple", "Cut"])? {
+        println!("{:?}", inputs);
     }
 
-    fn label(&self, id: FlagId) -> String {
-        self.id_to_label
-            .get(&id)
-            .cloned()
-            .unwrap_or(format!("?{}", id))
+    // ------------------------------------------------------------------------
+    // Phase 11: Queries (Programmatic Inspection)
+    // ------------------------------------------------------------------------
+    // The same facts as the trace, readable by code instead of by eye.
+    println!("\n=== Queries ===");
+    println!("Active:   {:?}", mind.active_labels());
+    if let Some(source @ Source::Derived { .. }) = mind.source_of("Snack") {
+        let causes: Vec<String> = source.causes().iter().map(|id| mind.label(*id)).collect();
+        println!("`Snack`:  derived from {:?}", causes);
     }
+    println!("`Ghost`:  {}", mind.is_active("Ghost"));
+
+    // ------------------------------------------------------------------------
+    // Phase 12: OR Gate (One Rule, Several Ways In)
+    // ------------------------------------------------------------------------
+    // "An Apple peels with a Knife OR a Blade"; the trace names the branch used.
+    println!("\n--- Learn `Peel`: `Apple` + (`Knife` | `Blade`) ---");
+    mind.learn_or(&["Apple"], &["Knife", "Blade"], "Peel");
+    mind.ponder();
+    mind.trace("Peel")?;
 
-    /// Add a logical rule: A + B + ... -> C
-    fn learn(&mut self, inputs: &[&str], output: &str) {
-        let t_ids: Vec<FlagId> = inputs.iter().map(|n| self.id(n)).collect();
-        let o_id = self.id(output);
+    // ------------------------------------------------------------------------
+    // Phase 13: Inhibition (Why Something Did NOT Happen)
+    // ------------------------------------------------------------------------
+    // "Eat a Snack unless Full": the trace explains the missing conclusion.
+    println!("\n--- Learn `Eat`: `Snack` - (`Full`) ---");
+    mind.learn_inhibited(&["Snack"], &["Full"], "Eat");
+    mind.inject(&["Full"]);
+    mind.ponder();
+    mind.trace("Eat")?;
 
-        self.rules.push(Rule {
-            triggers: t_ids,
-            output: o_id,
-        });
+    // ------------------------------------------------------------------------
+    // Phase 14: Cycle Check (Circular Knowledge)
+    // ------------------------------------------------------------------------
+    // "Steam needs Heat, and Steam gives off Heat": the rules chase each other.
+    println!("\n=== Cycle Check ===");
+    println!("Kitchen: {:?}", mind.has_cycles());
+    let mut kettle = Mind::new();
+    kettle.learn(&["Water", "Heat"], "Steam");
+    kettle.learn(&["Steam"], "Heat");
+    if let Some(cycle) = kettle.has_cycles() {
+        let cycle: Vec<String> = cycle.iter().map(|id| kettle.label(*id)).collect();
+        println!("Kettle:  {:?}", cycle);
     }
 
-    // ========================================================================
-    // Runtime Execution (Inference)
-    // ========================================================================
+    // ------------------------------------------------------------------------
+    // Phase 15: Graph Export (Graphviz DOT)
+    // ------------------------------------------------------------------------
+    // Pipe into `dot -Tpng` to draw the reasoning.
+    println!("\n=== DOT: `Separation` ===");
+    print!("{}", mind.to_dot(Some("Separation")));
 
-    /// Inject initial facts (Stimuli).
-    fn inject(&mut self, inputs: &[&str]) {
-        for name in inputs {
-            let id = self.id(name);
-            self.active_memory.insert(id, Source::Input);
-            println!("[Input] + `{}`", style(name).green());
-        }
+    // ------------------------------------------------------------------------
+    // Phase 16: Structured Trace (JSON for UIs)
+    // ------------------------------------------------------------------------
+    println!("\n=== JSON: `Fruit` ===");
+    if let Some(json) = mind.trace_json("Fruit") {
+        println!("{}", json);
     }
 
-    /// Run one cycle of thought processing.
-    /// Returns true if the mind state changed (new thoughts derived).
-    fn tick(&mut self, tick_count: usize) -> bool {
-        let mut new_facts: Vec<(FlagId, Vec<FlagId>)> = Vec::new();
+    // ------------------------------------------------------------------------
+    // Phase 17: Rules From Text (DSL)
+    // ------------------------------------------------------------------------
+    // The same kind of knowledge, written as a config file instead of code.
+    println!("\n=== Rules From Text ===");
+    let mut kitchen = Mind::new();
+    let loaded = kitchen.load_rules(
+        r#"
+        # Tools
+        Knife -> Sharp
+        Sharp, Solid, Cut -> Separation
+        Separation, Fruit -> "Fruit Slices"
+        "Fruit Slices" - Rotten -> Snack   # Never snack on rotten fruit
+        "#,
+    );
+    println!("Loaded: {:?}", loaded);
+    if let Err(err) = kitchen.load_rules("Knife, -> Sharp") {
+        println!("Broken: {}", err);
+    }
 
-        // 1. Scan Rules against Active Memory
-        for rule in &self.rules {
-            // Optimization: Don't re-derive known facts
-            if self.active_memory.contains_key(&rule.output) {
-                continue;
-            }
+    // ------------------------------------------------------------------------
+    // Phase 18: What-If (Counterfactual Planning)
+    // ------------------------------------------------------------------------
+    // Imagine picking up a fruit before doing it; memory stays as it is.
+    println!("\n=== What If ===");
+    kitchen.inject(&["Knife", "Cut"]);
+    kitchen.ponder();
+    println!(
+        "With `Fruit`, `Solid`: {:?}",
+        kitchen.what_if(&["Fruit", "Solid"])
+    );
+    println!("Still active: {:?}", kitchen.active_labels());
 
-            // Logic Gate: AND (All triggers must be present)
-            let all_met = rule
-                .triggers
-                .iter()
-                .all(|t| self.active_memory.contains_key(t));
+    // ------------------------------------------------------------------------
+    // Phase 19: Shortest Explanation (Skipping the Long Way Round)
+    // ------------------------------------------------------------------------
+    // `Dough` was baked the slow way; an `Oven` arriving later offers a shortcut.
+    println!("\n=== Shortest Explanation: `Bread` ===");
+    let mut bakery = Mind::new();
+    bakery.learn(&["Dough"], "Kneaded");
+    bakery.learn(&["Kneaded"], "Risen");
+    bakery.learn(&["Risen"], "Shaped");
+    bakery.learn(&["Shaped"], "Bread");
+    bakery.learn(&["Dough", "Oven"], "Bread");
+    bakery.inject(&["Dough"]);
+    bakery.ponder();
+    bakery.inject(&["Oven"]);
+    bakery.ponder();
+    let shortest = bakery.explain_shortest("Bread").unwrap_or_default();
+    let labels: Vec<String> = shortest.iter().map(|id| bakery.label(*id)).collect();
+    println!("Recorded: {:?}", bakery.supporting_inputs("Bread"));
+    println!("Shortest: {:?}", labels);
 
-            if all_met {
-                new_facts.push((rule.output, rule.triggers.clone()));
-            }
-        }
+    // ------------------------------------------------------------------------
+    // Phase 20: Checkpoints (Explicit Branches)
+    // ------------------------------------------------------------------------
+    // Unlike `what_if`, the branch really happens, and is then rolled back.
+    println!("\n=== Checkpoint ===");
+    let checkpoint = kitchen.snapshot();
+    kitchen.inject(&["Fruit", "Solid"]);
+    kitchen.ponder();
+    println!("Branch:   {:?}", kitchen.active_labels());
+    kitchen.restore(checkpoint);
+    println!("Restored: {:?}", kitchen.active_labels());
 
-        if new_facts.is_empty() {
-            return false;
-        }
+    // ------------------------------------------------------------------------
+    // Phase 21: Merging Knowledge (Kitchen + Safety Modules)
+    // ------------------------------------------------------------------------
+    println!("\n=== Merge: Safety Into Kitchen ===");
+    let mut safety = Mind::new();
+    safety.learn(&["Knife"], "Sharp"); // Already known to the kitchen
+    safety.learn(&["Sharp", "Child"], "Danger");
+    let report = kitchen.merge(&safety);
+    println!("Added {}, skipped {}", report.added, report.skipped);
+    kitchen.inject(&["Child"]);
+    kitchen.ponder();
 
-        // 2. Commit new facts to memory (Neuron Activation)
-        let header = format!("[Tick {tick_count}]");
-        let padding = " ".repeat(header.len());
-
-        for (i, (output_id, causes)) in new_facts.iter().enumerate() {
-            // Log format: `CauseA`, `CauseB` ---> `Result`
-            let cause_str = causes
-                .iter()
-                .map(|id| format!("`{}`", self.label(*id)))
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            let out_str = format!("`{}`", self.label(*output_id));
-
-            let message = format!("{} ---> {}", cause_str, style(out_str).yellow().bold());
-            if i == 0 {
-                println!("{header} {message}");
-            } else {
-                println!("{padding} {message}");
-            }
+    // ------------------------------------------------------------------------
+    // Phase 22: Metrics (How Hard Was That?)
+    // ------------------------------------------------------------------------
+    println!("\n=== Metrics: Baking Again ===");
+    bakery.reset_memory();
+    bakery.inject(&["Dough"]);
+    let stats = bakery.ponder_with_stats();
+    println!(
+        "Ticks: {}, derived: {}, rules checked: {}",
+        stats.ticks_run, stats.facts_derived, stats.rules_evaluated
+    );
+    println!("Lifetime ticks: {}", bakery.stats().ticks_run);
 
-            self.active_memory.insert(
-                *output_id,
-                Source::Derived {
-                    causes: causes.clone(),
-                },
-            );
+    // ------------------------------------------------------------------------
+    // Phase 23: Builder (Programmatic Construction)
+    // ------------------------------------------------------------------------
+    println!("\n=== Builder ===");
+    let built = MindBuilder::new()
+        .rule(&["Seed", "Water"], "Sprout")
+        .inhibited(&["Sprout"], &["Frost"], "Flower")
+        .input("Seed")
+        .input("Water")
+        .build();
+    match built {
+        Ok(mut garden) => {
+            garden.ponder();
+            println!("Garden: {:?}", garden.active_labels());
         }
-
-        true
+        Err(err) => println!("Refused: {}", err),
+    }
+    if let Err(err) = MindBuilder::new().rule(&["Echo"], "Echo").build() {
+        println!("Refused: {}", err);
     }
 
-    // ========================================================================
-    // Analysis (White Box Debugging)
-    // ========================================================================
-
-    /// Visualizes the logic chain for a specific concept.
-    fn trace(&self, target: &str) {
-        println!("\n=== Trace: `{}` ===", target);
+    // ------------------------------------------------------------------------
+    // Phase 24: Sensor Stream (Continuous Operation)
+    // ------------------------------------------------------------------------
+    // Readings arrive one by one; the mind stays awake between them.
+    println!("\n=== Sensor Stream ===");
+    let mut greenhouse = Mind::new();
+    greenhouse.learn(&["Sunrise"], "Daylight");
+    greenhouse.learn(&["Daylight", "Dry Soil"], "Open Valve");
+    greenhouse.learn(&["Open Valve", "Low Tank"], "Refill Alert");
+    let readings = ["Dry Soil", "Low Tank", "Sunrise", "Wind"];
+    for reading in readings {
+        let derived = greenhouse.feed(reading);
+        println!("`{}` -> {:?}", reading, derived);
+    }
 
-        if let Some(&id) = self.label_to_id.get(target) {
-            if self.active_memory.contains_key(&id) {
-                let mut builder = TreeBuilder::new(self.node_text(id));
-                self.build_tree_recursive(id, &mut builder);
-                print_tree(&builder.build()).unwrap();
-            } else {
-                println!("Memory does not contain `{}`", target);
+    // ------------------------------------------------------------------------
+    // Phase 25: Iterating Memory (No Intermediate Vec)
+    // ------------------------------------------------------------------------
+    println!("\n=== Greenhouse Memory ===");
+    for (label, source) in greenhouse.iter_active_labels() {
+        match source {
+            Source::Input => println!("{:<14} input", label),
+            Source::Derived { .. } => {
+                let causes: Vec<String> = source
+                    .causes()
+                    .iter()
+                    .map(|c| greenhouse.label(*c))
+                    .collect();
+                println!("{:<14} from {:?}", label, causes);
             }
-        } else {
-            println!("Unknown concept: `{}`", target);
         }
     }
 
-    fn node_text(&self, id: FlagId) -> String {
-        let label = self.label(id);
-        match self.active_memory.get(&id) {
-            Some(Source::Input) => format!("`{}` (Input)", label),
-            Some(Source::Derived { .. }) => format!("`{}`", label),
-            None => format!("`{}` (MISSING)", label),
-        }
+    // ------------------------------------------------------------------------
+    // Phase 26: Custom Output Sink (Quiet Library Use)
+    // ------------------------------------------------------------------------
+    // The console log is only the default reporter; this one keeps a tally.
+    println!("\n=== Tally Reporter ===");
+    let (sink, tally) = mpsc::channel();
+    greenhouse.set_reporter(Tally(sink));
+    greenhouse.reset_memory();
+    greenhouse.inject(&["Sunrise", "Dry Soil"]);
+    greenhouse.ponder();
+    for line in tally.try_iter() {
+        println!("{}", line);
     }
+    greenhouse.set_reporter(NullReporter);
+    greenhouse.feed("Low Tank");
+    println!("Silently derived: {}", greenhouse.is_active("Refill Alert"));
 
-    fn build_tree_recursive(&self, id: FlagId, builder: &mut TreeBuilder) {
-        if let Some(Source::Derived { causes }) = self.active_memory.get(&id) {
-            for &cause_id in causes {
-                builder.begin_child(self.node_text(cause_id));
-                self.build_tree_recursive(cause_id, builder);
-                builder.end_child();
+    // ------------------------------------------------------------------------
+    // Phase 27: Synonyms (One Flag, Several Names)
+    // ------------------------------------------------------------------------
+    println!("\n=== Aliases ===");
+    let mut drawer = Mind::new();
+    drawer.learn(&["Knife"], "Sharp");
+    drawer.alias("Knife", "Blade")?;
+    drawer.inject(&["Blade"]);
+    drawer.ponder();
+    println!("Active: {:?}", drawer.active_labels());
+    if let Err(err) = drawer.alias("Knife", "Sharp") {
+        println!("Refused: {}", err);
+    }
+
+    // ------------------------------------------------------------------------
+    // Phase 28: Payloads (When Was Each Fact Seen?)
+    // ------------------------------------------------------------------------
+    println!("\n=== Payloads ===");
+    let mut watch: Mind<u64> = Mind::with_payloads();
+    watch.learn(&["Smoke", "Heat"], "Fire");
+    watch.combine_payloads(|seen_at| seen_at.iter().map(|t| **t).max().unwrap_or(0));
+    watch.inject_with("Smoke", 1200);
+    watch.inject_with("Heat", 1215);
+    watch.ponder();
+    println!("`Fire` as of t={:?}", watch.data_of("Fire"));
+
+    // ------------------------------------------------------------------------
+    // Phase 29: Backward Chaining (Ask About One Goal)
+    // ------------------------------------------------------------------------
+    println!("\n=== Prove ===");
+    let mut trip = Mind::new();
+    trip.learn(&["Passport", "Ticket"], "Board");
+    trip.learn(&["Board"], "Fly");
+    trip.rule(&["Fly"], &["Storm"], "Arrive");
+    trip.learn(&["Arrive"], "Fly"); // A cycle the search must not chase
+    trip.inject(&["Passport", "Ticket"]);
+    for goal in ["Arrive", "Hotel"] {
+        match trip.prove(goal) {
+            Some(proof) => {
+                let labels: Vec<String> = proof.iter().map(|id| trip.label(*id)).collect();
+                println!("`{}` provable from {:?}", goal, labels);
             }
+            None => println!("`{}` not provable", goal),
         }
     }
-}
+    trip.inject(&["Storm"]);
+    println!("With `Storm`: {:?}", trip.prove("Arrive"));
+    println!("Memory untouched: {:?}", trip.active_labels());
 
-fn main() {
-    let mut mind = Mind::new();
+    // ------------------------------------------------------------------------
+    // Phase 30: Reproducible Runs (Byte-Identical Logs)
+    // ------------------------------------------------------------------------
+    println!("\n=== Reproducible Runs ===");
+    let run = |warm_up: &[&str]| {
+        let mut mind = Mind::new();
+        for label in warm_up {
+            mind.id(label); // Other ids, same rules
+        }
+        let (sink, log) = mpsc::channel();
+        mind.set_reporter(Tally(sink));
+        mind.learn(&["Dough", "Oven"], "Bread");
+        mind.learn(&["Dough", "Pan"], "Crepe");
+        mind.learn(&["Bread", "Butter"], "Toast");
+        mind.learn(&["Crepe", "Butter"], "Breakfast");
+        mind.inject(&["Dough", "Oven", "Pan", "Butter"]);
+        mind.ponder();
+        let mut out: Vec<String> = log.try_iter().collect();
+        out.push(mind.decision_report());
+        out.join("\n")
+    };
+    let first = run(&[]);
+    let second = run(&["Toast", "Breakfast", "Crepe", "Bread"]);
+    println!("{}", first);
+    println!("Identical: {}", first == second);
 
     // ------------------------------------------------------------------------
-    // Phase 1: Pre-compilation (Hardcoding the Static Logic Graph)
+    // Phase 31: Rule Graph Export (Design-Time DOT)
     // ------------------------------------------------------------------------
-    // In a real scenario, the Compiler generates this from config files.
+    // The whole topology, nothing injected: gates for groups, red bars for forbids.
+    println!("\n=== DOT: Rule Base ===");
+    let mut door = Mind::new();
+    door.rule_or(&["Badge"], &["Pin", "Face"], &["Lockdown"], "Unlock");
+    door.rule_threshold(&["Motion", "Noise", "Heat"], 2, "Occupied")?;
+    door.learn(&["Unlock", "Occupied"], "LightsOn");
+    print!("{}", door.rules_to_dot());
 
-    // Inheritance: A implies B
-    mind.learn(&["Knife"], "Sharp");
-    mind.learn(&["Apple"], "Fruit");
-    mind.learn(&["Fruit"], "Solid");
+    // ------------------------------------------------------------------------
+    // Phase 32: Snapshot Diff (What Did the Stimulus Change?)
+    // ------------------------------------------------------------------------
+    println!("\n=== Snapshot Diff ===");
+    let mut hall = Mind::new();
+    hall.rule(&["Switch"], &["Outage"], "LightOn");
+    hall.learn(&["Smoke"], "Alarm");
+    hall.inject(&["Switch"]);
+    hall.inject_weighted(&[("Smoke", 0.6)]);
+    hall.ponder();
+    let before = hall.snapshot();
+    hall.inject_weighted(&[("Smoke", 0.9)]);
+    hall.inject(&["Outage"]);
+    hall.retract(&["Switch"]);
+    hall.ponder();
+    print!("{}", hall.diff(&before, &hall.snapshot()));
 
-    // Interaction: Logic calculation
-    // "If something Sharp cuts something Solid, Separation occurs"
-    mind.learn(&["Sharp", "Solid", "Cut"], "Separation");
+    // ------------------------------------------------------------------------
+    // Phase 33: Why Not? (Explaining an Absence)
+    // ------------------------------------------------------------------------
+    println!("\n=== Why Not: `Fruit Slices` ===");
+    let mut prep = Mind::new();
+    prep.learn(&["Knife"], "Sharp");
+    prep.learn(&["Apple"], "Fruit");
+    prep.learn(&["Fruit"], "Solid");
+    prep.learn(&["Sharp", "Solid", "Cut"], "Separation");
+    prep.learn(&["Separation", "Fruit"], "Fruit Slices");
+    prep.rule(&["Fruit", "Blender"], &["LidOff"], "Fruit Slices");
+    prep.inject(&["Apple", "Cut", "Blender", "LidOff"]);
+    prep.ponder();
+    println!("{}", prep.explain_absence("Fruit Slices"));
+    println!("{}", prep.explain_absence("Lemonade"));
 
-    // Concept Discovery:
-    // "If a Fruit is Separated, it becomes Fruit Slices"
-    mind.learn(&["Separation", "Fruit"], "Fruit Slices");
+    // ------------------------------------------------------------------------
+    // Phase 34: Resuming Memory (Across Process Runs)
+    // ------------------------------------------------------------------------
+    // Memory is saved by label; the next run loads the same rules in another
+    // id order, then picks the facts back up.
+    println!("\n=== Resume: Saved Memory ===");
+    let kitchen_rules = |mind: &mut Mind| {
+        mind.learn(&["Knife"], "Sharp");
+        mind.learn(&["Apple"], "Fruit");
+        mind.learn(&["Fruit"], "Solid");
+        mind.learn(&["Sharp", "Solid", "Cut"], "Separation");
+        mind.learn(&["Separation", "Fruit"], "Fruit Slices");
+    };
+    let memory_path = std::env::temp_dir().join("bi-simple-flag-memory.json");
+
+    let mut first_run = Mind::new();
+    first_run.set_reporter(NullReporter);
+    kitchen_rules(&mut first_run);
+    first_run.inject(&["Knife", "Apple", "Cut"]);
+    first_run.ponder();
+    if let Err(err) = first_run.save_memory(&memory_path) {
+        println!("Error: {}", err);
+    }
+
+    let mut next_run = Mind::new();
+    next_run.set_reporter(NullReporter);
+    next_run.id("Cut"); // Another id order than the first run
+    next_run.id("Fruit Slices");
+    kitchen_rules(&mut next_run);
+    match next_run.load_memory(&memory_path) {
+        Ok(orphans) => println!("Loaded, orphans: {:?}", orphans),
+        Err(err) => println!("Error: {}", err),
+    }
+    let same_traces = first_run
+        .active_labels()
+        .iter()
+        .all(|label| first_run.trace_json(label) == next_run.trace_json(label));
+    println!("Same traces: {}", same_traces);
+
+    // Without the slicing rule, `Fruit Slices` cannot be justified any more
+    let mut edited = Mind::new();
+    edited.set_reporter(NullReporter);
+    edited.learn(&["Knife"], "Sharp");
+    edited.learn(&["Apple"], "Fruit");
+    edited.learn(&["Fruit"], "Solid");
+    edited.learn(&["Sharp", "Solid", "Cut"], "Separation");
+    match edited.load_memory(&memory_path) {
+        Ok(orphans) => println!("Edited rules, kept as inputs: {:?}", orphans),
+        Err(err) => println!("Error: {}", err),
+    }
+    let _ = std::fs::remove_file(&memory_path);
 
     // ------------------------------------------------------------------------
-    // Phase 2: Runtime (Inference)
+    // Phase 35: Portable Ids (Sorted Label Order)
     // ------------------------------------------------------------------------
-    println!("--- Simulation Start ---");
+    // The same rules in another order: first-seen ids differ, sorted ids do not.
+    println!("\n=== Portable Ids ===");
+    let mut forward = Mind::new();
+    forward.freeze_labels(&["Knife", "Sharp", "Apple", "Fruit"])?;
+    forward.learn(&["Knife"], "Sharp");
+    forward.learn(&["Apple"], "Fruit");
+    let backward = MindBuilder::new()
+        .rule(&["Apple"], "Fruit")
+        .rule(&["Knife"], "Sharp")
+        .sorted_ids()
+        .build();
+    println!("Sorted ids: {:?}", forward.label_map());
+    if let Ok(backward) = backward {
+        println!("Same ids: {}", forward.label_map() == backward.label_map());
+    }
+    if let Err(err) = forward.freeze_labels(&["Zest"]) {
+        println!("Refused: {}", err);
+    }
 
-    // Scenario: User holds a Knife, an Apple, and performs Cut action.
-    mind.inject(&["Knife", "Apple", "Cut"]);
+    // Data keyed by first-seen ids, moved onto the sorted assignment
+    let mut legacy = Mind::new();
+    legacy.set_reporter(NullReporter);
+    legacy.learn(&["Knife"], "Sharp");
+    legacy.learn(&["Apple"], "Fruit");
+    legacy.inject(&["Knife"]);
+    legacy.ponder();
+    let old_ids = legacy.tick_diff().added;
+    let remap = forward.remap_ids(&legacy.label_map());
+    let mut new_ids: Vec<_> = old_ids.iter().filter_map(|id| remap.get(id)).collect();
+    new_ids.sort();
+    println!("Last diff {:?} becomes {:?}", old_ids, new_ids);
 
-    let mut tick = 1;
-    loop {
-        if !mind.tick(tick) {
-            break;
-        }
-        tick += 1;
+    // ------------------------------------------------------------------------
+    // Phase 36: Spreading Activation (What Is Near `Knife`?)
+    // ------------------------------------------------------------------------
+    // Read-only: strength halves per hop through shared rules.
+    println!("\n=== Spreading Activation: `Knife` ===");
+    let mut nearby: Vec<(String, f32)> = mind
+        .spread_activation("Knife", 0.5, 0.1)
+        .into_iter()
+        .map(|(id, strength)| (mind.label(id), strength))
+        .collect();
+    nearby.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (label, strength) in nearby {
+        println!("{:.2} {}", strength, label);
     }
-    println!("--- Simulation Stable ---\n");
 
     // ------------------------------------------------------------------------
-    // Phase 3: Explainability (White Box Check)
+    // Phase 37: Mermaid Export (Proofs for Markdown)
     // ------------------------------------------------------------------------
-    // Prove that the AI knows WHY it thinks "Fruit Slices" exist.
-    mind.trace("Fruit Slices");
+    println!("\n=== Mermaid: `Fruit Slices` ===");
+    match mind.trace_mermaid("Fruit Slices") {
+        Some(chart) => print!("{}", chart),
+        None => println!("(`Fruit Slices` is not active)"),
+    }
+
+    // ------------------------------------------------------------------------
+    // Phase 38: Introspection (How Big Is This Mind?)
+    // ------------------------------------------------------------------------
+    println!("\n=== Introspection ===");
+    print!("{}", mind.introspect());
+
+    // ------------------------------------------------------------------------
+    // Phase 39: Plain-Text Trace (Fixtures and Log Files)
+    // ------------------------------------------------------------------------
+    println!("\n=== Plain Text: `Fruit Slices` ===");
+    if let Some(text) = mind.trace_text("Fruit Slices") {
+        print!("{}", text);
+    }
+
+    // ------------------------------------------------------------------------
+    // Phase 40: Batch Tracing (Several Goals, Shared Proofs)
+    // ------------------------------------------------------------------------
+    // `Separation` is part of the `Fruit Slices` proof: the JSON lists it once.
+    let goals = ["Fruit Slices", "Separation"];
+    mind.trace_all(&goals)?;
+    println!("\n=== Proof Forest (JSON) ===");
+    println!("{}", mind.trace_json_all(&goals));
+
+    // ------------------------------------------------------------------------
+    // Phase 41: Provenance Timestamps (When Was It Concluded?)
+    // ------------------------------------------------------------------------
+    // Stamps count every tick of the mind, across ponders. Replaying the
+    // current inputs on a reset memory makes tick 1 the start of this run.
+    let inputs = mind.inputs();
+    let inputs: Vec<&str> = inputs.iter().map(String::as_str).collect();
+    mind.set_reporter(NullReporter);
+    mind.reset_memory();
+    mind.inject(&inputs);
+    mind.ponder();
+    println!("\n=== Derived in the First Two Ticks ===");
+    let early: Vec<String> = mind
+        .derived()
+        .into_iter()
+        .filter(|label| mind.derived_at(label).is_some_and(|tick| tick <= 2))
+        .collect();
+    println!("{:?}", early);
+    mind.set_trace_ticks(true);
+    mind.trace("Fruit Slices")?;
+    mind.set_trace_ticks(false);
+
+    Ok(())
+}
+
+/// Forwards each derivation as one plain line, without colors.
+struct Tally(mpsc::Sender<String>);
+
+impl Reporter for Tally {
+    fn derived(&mut self, tick: usize, causes: &[String], output: &str, _value: f32) {
+        let _ = self.0.send(format!(
+            "tick {}: {} <= {}",
+            tick,
+            output,
+            causes.join(" & ")
+        ));
+    }
 }